@@ -69,12 +69,11 @@
 //! impl<T> MyStruct<T> {
 //!   /// Foo
 //!   pub(crate) fn construct(a: impl Into<T>, c: &Arc<Whatever>) -> Self where T: Clone {
-//!     Self {
-//!       a: a.into(),
-//!       b: "Bar".into(),
-//!       c: c.clone(),
-//!       d: Default::default(),
-//!     }
+//!     let a: T = a.into();
+//!     let c: Arc<Whatever> = c.clone();
+//!     let __field_b: String = "Bar".into();
+//!     let __field_d: Vec<u8> = Default::default();
+//!     Self { a, b: __field_b, c, d: __field_d }
 //!   }
 //! }
 //! ````
@@ -119,6 +118,75 @@
 //! assert_eq!(Foo::new(false).barness_level, 5);
 //! ```
 //!
+//! Arguments get converted before any value is computed, so `val` expressions see the converted
+//! values. Computed values can reference each other, including via inline format string captures:
+//! every computed value gets bound to a local before the struct is built, in the order their
+//! references require, regardless of the order the fields are declared in. Referenced ones get
+//! bound to their fields' names; the rest get hidden names, so they can't shadow fns or other items
+//! of the same name. Cyclic references are a compile error. Names bound by closure parameters or
+//! `let`s within a `val` expression & fns being called don't count as references.
+//!
+//! ```
+//! # use fancy_constructor::new;
+//! #[derive(new)]
+//! struct Greeting {
+//!   #[new(into)]
+//!   name: String,
+//!   #[new(val(format!("Hello, {name}!")))]
+//!   message: String,
+//!   #[new(val(message.len()))]
+//!   len: usize,
+//! }
+//!
+//! let greeting = Greeting::new("world");
+//! assert_eq!(greeting.message, "Hello, world!");
+//! assert_eq!(greeting.len, 13);
+//!
+//! // The converted `String` is still around when the struct gets built
+//! #[derive(new)]
+//! struct Label {
+//!   #[new(into)]
+//!   text: String,
+//!   #[new(val(text.len()))]
+//!   width: usize,
+//! }
+//!
+//! let label = Label::new("abc");
+//! assert_eq!((label.text.as_str(), label.width), ("abc", 3));
+//!
+//! use std::cmp::max;
+//!
+//! #[derive(new)]
+//! struct Counter {
+//!   #[new(val(format!("n={count}")))]
+//!   label: String,
+//!   #[new(val(3))]
+//!   count: u8,
+//!   #[new(default)]
+//!   max: u8,
+//!   #[new(val(max(count, { let max = 5; max })))]
+//!   limit: u8,
+//! }
+//!
+//! let counter = Counter::new();
+//! assert_eq!(counter.label, "n=3");
+//! assert_eq!(counter.limit, 5);
+//! ```
+//!
+//! Outputs:
+//!
+#![cfg_attr(doctest, doc = " ````no_test")]
+//! ```
+//! impl Greeting {
+//!   pub fn new(name: impl Into<String>) -> Self {
+//!     let name: String = name.into();
+//!     let message: String = format!("Hello, {name}!");
+//!     let __field_len: usize = message.len();
+//!     Self { name, message, len: __field_len }
+//!   }
+//! }
+//! ````
+//!
 //! </details>
 //!
 //! <details><summary>Custom constructor args</summary>
@@ -181,7 +249,76 @@
 //! ```
 //! impl MyEnum {
 //!   pub fn new(bar: Into<u8>) -> Self {
-//!     Self::Foo { bar: bar.into() }
+//!     let bar: u8 = bar.into();
+//!     Self::Foo { bar }
+//!   }
+//! }
+//! ````
+//!
+//! Marking multiple variants, or using the `all_variants` container option, generates a constructor
+//! per variant. Their names default to `new_` + the variant's snake-cased name.
+//!
+//! ```
+//! # use fancy_constructor::new;
+//! #[derive(new, Eq, PartialEq, Debug)]
+//! #[new(all_variants)]
+//! enum MyEnum {
+//!   FooBar(u8),
+//!   #[new(name(qux))]
+//!   Qux { #[new(default)] a: String },
+//! }
+//!
+//! assert_eq!(MyEnum::new_foo_bar(5), MyEnum::FooBar(5));
+//! assert_eq!(MyEnum::qux(), MyEnum::Qux { a: String::new() });
+//! ```
+//!
+//! Outputs:
+//!
+#![cfg_attr(doctest, doc = " ````no_test")]
+//! ```
+//! impl MyEnum {
+//!   pub fn new_foo_bar(f1: u8) -> Self {
+//!     Self::FooBar(f1)
+//!   }
+//!   pub fn qux() -> Self {
+//!     let __field_a: String = Default::default();
+//!     Self::Qux { a: __field_a }
+//!   }
+//! }
+//! ````
+//!
+//! Variants accept the same options as the container, overriding its values:
+//!
+//! ```
+//! # use fancy_constructor::new;
+//! #[derive(new, Eq, PartialEq, Debug)]
+//! #[new(vis(pub(crate)), args(base: u8))]
+//! enum MyEnum {
+//!   #[new(const_fn, args(extra: u8))]
+//!   Sum(#[new(val(base + extra))] u8),
+//!
+//!   #[new(vis(), name(twice), comment("Double trouble"))]
+//!   Twice(#[new(val(base * 2))] u8),
+//! }
+//!
+//! const SUM: MyEnum = MyEnum::new_sum(1, 2);
+//! assert_eq!(SUM, MyEnum::Sum(3));
+//! assert_eq!(MyEnum::twice(4), MyEnum::Twice(8));
+//! ```
+//!
+//! Outputs:
+//!
+#![cfg_attr(doctest, doc = " ````no_test")]
+//! ```
+//! impl MyEnum {
+//!   pub(crate) const fn new_sum(base: u8, extra: u8) -> Self {
+//!     let __field_f1: u8 = base + extra;
+//!     Self::Sum(__field_f1)
+//!   }
+//!   /// Double trouble
+//!   fn twice(base: u8) -> Self {
+//!     let __field_f1: u8 = base * 2;
+//!     Self::Twice(__field_f1)
 //!   }
 //! }
 //! ````
@@ -228,64 +365,1484 @@
 //!
 //! </details>
 //!
-//! <details><summary>Invalid inputs</summary>
+//! <details><summary>Validation</summary>
 //!
-//! ```compile_fail
-//! #[derive(fancy_constructor::new)]
-//! enum Foo {
-//!   Bar, // no variants marked with `#[new]`
+//! The `validate` option makes the constructor fallible. The validator gets called with a reference
+//! to the constructed value and its error gets converted into the `error` type via `?`.
+//!
+//! ```
+//! # use fancy_constructor::new;
+//! #[derive(new, PartialEq, Eq, Debug)]
+//! #[new(validate(Self::check), error(String), panicking)]
+//! struct Range {
+//!   min: u8,
+//!   max: u8,
+//! }
+//!
+//! impl Range {
+//!   fn check(&self) -> Result<(), String> {
+//!     if self.min <= self.max {
+//!       Ok(())
+//!     } else {
+//!       Err(format!("{} > {}", self.min, self.max))
+//!     }
+//!   }
 //! }
+//!
+//! assert_eq!(Range::try_new(1, 2), Ok(Range { min: 1, max: 2 }));
+//! assert_eq!(Range::try_new(2, 1), Err("2 > 1".into()));
+//! assert_eq!(Range::new(1, 1), Range { min: 1, max: 1 });
+//!
+//! let result = std::panic::catch_unwind(|| Range::new(2, 1));
+//! assert!(result.is_err());
 //! ```
 //!
-//! ```compile_fail
-//! #[derive(fancy_constructor::new)]
-//! enum Foo {
-//!   #[new] Bar, // multiple variants marked with `#[new]`
-//!   #[new] Qux,
+//! Outputs:
+//!
+#![cfg_attr(doctest, doc = " ````no_test")]
+//! ```
+//! impl Range {
+//!   pub fn try_new(min: u8, max: u8) -> Result<Self, String> {
+//!     let __constructed = Self { min, max };
+//!     (Self::check)(&__constructed)?;
+//!     Ok(__constructed)
+//!   }
+//!
+//!   pub fn new(min: u8, max: u8) -> Self {
+//!     match Self::try_new(min, max) {
+//!       Ok(v) => v,
+//!       Err(e) => panic!("{}", e),
+//!     }
+//!   }
 //! }
+//! ````
+//!
+//! </details>
+//!
+//! <details><summary>Field validation</summary>
+//!
+//! Fields' `validate` options check their arguments before the value gets constructed. A path or
+//! closure gets called with a reference to the argument & should return `Result<(), impl Display>`;
+//! any other expression should evaluate to a `bool`. Every failure gets collected into a generated
+//! `{StructName}ConstructError`.
+//!
 //! ```
+//! # use fancy_constructor::new;
+//! #[derive(new, Debug)]
+//! struct User {
+//!   #[new(into, validate(|v: &String| if v.is_empty() { Err("empty") } else { Ok(()) }))]
+//!   name: String,
 //!
-//! ```compile_fail
-//! #[derive(fancy_constructor::new)]
-//! union Foo { // Unions not supported
-//!   bar: u8,
-//!   qux: u8,
+//!   #[new(validate(age < 150))]
+//!   age: u8,
 //! }
+//!
+//! assert!(User::try_new("Bob", 30).is_ok());
+//!
+//! let err = User::try_new("", 200).unwrap_err();
+//! assert_eq!(err.errors, vec![
+//!   ("name", "empty".to_string()),
+//!   ("age", "validation failed".to_string()),
+//! ]);
+//! assert_eq!(err.to_string(), "invalid `name`: empty; invalid `age`: validation failed");
+//! ```
+//!
+//! Outputs:
+//!
+#![cfg_attr(doctest, doc = " ````no_test")]
 //! ```
+//! impl User {
+//!   pub fn try_new(name: impl Into<String>, age: u8) -> Result<Self, UserConstructError> {
+//!     let mut __errors = Vec::new();
+//!     let name: String = name.into();
+//!     if let Err(e) = (|v: &String| if v.is_empty() { Err("empty") } else { Ok(()) })(&name) {
+//!       __errors.push(("name", e.to_string()));
+//!     }
+//!     if !(age < 150) {
+//!       __errors.push(("age", String::from("validation failed")));
+//!     }
+//!     if !__errors.is_empty() {
+//!       return Err(From::from(UserConstructError { errors: __errors }));
+//!     }
+//!     Ok(Self { name, age })
+//!   }
+//! }
+//!
+//! #[derive(Debug, Clone, PartialEq, Eq)]
+//! struct UserConstructError {
+//!   pub errors: Vec<(&'static str, String)>,
+//! }
+//!
+//! impl Display for UserConstructError { /* ... */ }
+//! impl Error for UserConstructError {}
+//! ````
 //!
 //! </details>
-
-#![deny(clippy::correctness, clippy::suspicious)]
-#![warn(clippy::complexity, clippy::perf, clippy::style, clippy::pedantic)]
-#![allow(clippy::needless_pass_by_value)]
-#![warn(missing_docs)]
-
-mod options;
-mod parse;
-mod tokenise;
-mod types;
-
-extern crate proc_macro;
-
-use crate::types::FieldsSource;
-use proc_macro2::Ident;
-use syn::{Attribute, Type};
-
-const ATTR_NAME: &str = "new";
-
-/// See [crate-level docs](crate) for a usage and output example.
-///
-/// # Container options
-///
-/// | Opt | Default | Description |
-/// | --- | --- | --- |
-/// | `new(const_fn)` | `false` | Whether to make the constructor a `const fn` |
-/// | `default` | `false` | Generate a [`Default`](::core::default::Default) implementation if the constructor ends up with no arguments. |
-/// | `new(vis(visibility))` | `pub` | The visibility of the constructor |
-/// | `new(name(ident))` | `new` | Constructor fn name |
-/// | `new(comment(literal))` | | A doc comment to add to the constructor |
-/// | `new(bounds(T: Whatever))` | | Generic type bounds for the implementation |
-/// | `new(args(foo: u8, bar: String))` | | Additional arguments to add to the **beginning** of the args list |
+//!
+//! <details><summary>Fallible conversions</summary>
+//!
+//! `try_into` fields accept any [`TryInto<T>`](::core::convert::TryInto) argument, making the
+//! constructor fallible. A sole `try_into` field's conversion error gets returned as-is; multiple
+//! fallible fields need an `error` type that every error converts into.
+//!
+//! ```
+//! # use fancy_constructor::new;
+//! # use std::num::TryFromIntError;
+//! #[derive(new, PartialEq, Eq, Debug)]
+//! struct Foo {
+//!   #[new(try_into)]
+//!   small: u8,
+//!   big: u64,
+//! }
+//!
+//! assert_eq!(Foo::try_new(5u64, 5), Ok(Foo { small: 5, big: 5 }));
+//! assert!(Foo::try_new(256u64, 5).is_err());
+//!
+//! #[derive(Debug)]
+//! enum MyError {
+//!   Int(TryFromIntError),
+//! }
+//!
+//! impl From<TryFromIntError> for MyError {
+//!   fn from(e: TryFromIntError) -> Self {
+//!     Self::Int(e)
+//!   }
+//! }
+//!
+//! #[derive(new)]
+//! #[new(error(MyError))]
+//! struct Bar(#[new(try_into)] u8, #[new(try_into)] i8);
+//!
+//! assert!(matches!(Bar::try_new(1u32, 300i32), Err(MyError::Int(_))));
+//! ```
+//!
+//! Outputs:
+//!
+#![cfg_attr(doctest, doc = " ````no_test")]
+//! ```
+//! impl Foo {
+//!   pub fn try_new<__TryInto0: TryInto<u8>>(small: __TryInto0, big: u64)
+//!     -> Result<Self, <__TryInto0 as TryInto<u8>>::Error> {
+//!     let small: u8 = TryInto::try_into(small)?;
+//!     Ok(Self { small, big })
+//!   }
+//! }
+//!
+//! impl Bar {
+//!   pub fn try_new<__TryInto0: TryInto<u8>, __TryInto1: TryInto<i8>>(f1: __TryInto0, f2: __TryInto1)
+//!     -> Result<Self, MyError>
+//!     where MyError: From<<__TryInto0 as TryInto<u8>>::Error>,
+//!           MyError: From<<__TryInto1 as TryInto<i8>>::Error> {
+//!     let f1: u8 = TryInto::try_into(f1)?;
+//!     let f2: i8 = TryInto::try_into(f2)?;
+//!     Ok(Self(f1, f2))
+//!   }
+//! }
+//! ````
+//!
+//! </details>
+//!
+//! <details><summary>Parsing string arguments</summary>
+//!
+//! `parse` fields accept any `impl AsRef<str>` and parse it via [`FromStr`](::core::str::FromStr).
+//! Parse errors get collected into the generated `{StructName}ConstructError` along with the
+//! argument's name. Validators of parsed fields, or ones referencing parsed arguments, run once
+//! everything has been parsed; the rest run even if parsing fails so every failure gets reported.
+//!
+//! ```
+//! # use fancy_constructor::new;
+//! #[derive(new, Debug, PartialEq)]
+//! struct Config {
+//!   #[new(parse)]
+//!   port: u16,
+//!
+//!   #[new(parse, validate(ratio <= 1.0))]
+//!   ratio: f32,
+//!
+//!   #[new(validate(!name.is_empty()))]
+//!   name: String,
+//! }
+//!
+//! let config = Config::try_new("80", "0.5", "main".into());
+//! assert_eq!(config, Ok(Config { port: 80, ratio: 0.5, name: "main".into() }));
+//!
+//! let err = Config::try_new("eighty", "nope", String::new()).unwrap_err();
+//! assert_eq!(err.errors.len(), 3);
+//! assert_eq!(err.errors[0].0, "port");
+//! assert_eq!(err.errors[1].0, "ratio");
+//! assert_eq!(err.errors[2].0, "name");
+//!
+//! let err = Config::try_new(String::from("80"), "2", "main".into()).unwrap_err();
+//! assert_eq!(err.to_string(), "invalid `ratio`: validation failed");
+//! ```
+//!
+//! Outputs:
+//!
+#![cfg_attr(doctest, doc = " ````no_test")]
+//! ```
+//! impl Config {
+//!   pub fn try_new(port: impl AsRef<str>, ratio: impl AsRef<str>, name: String)
+//!     -> Result<Self, ConfigConstructError> {
+//!     let mut __errors = Vec::new();
+//!     let port = match <u16 as FromStr>::from_str(port.as_ref()) {
+//!       Ok(v) => Some(v),
+//!       Err(e) => {
+//!         __errors.push(("port", e.to_string()));
+//!         None
+//!       }
+//!     };
+//!     let ratio = match <f32 as FromStr>::from_str(ratio.as_ref()) { /* same as above */ };
+//!     if !(!name.is_empty()) {
+//!       __errors.push(("name", String::from("validation failed")));
+//!     }
+//!     let (Some(port), Some(ratio)) = (port, ratio) else {
+//!       return Err(From::from(ConfigConstructError { errors: __errors }));
+//!     };
+//!     if !(ratio <= 1.0) {
+//!       __errors.push(("ratio", String::from("validation failed")));
+//!     }
+//!     if !__errors.is_empty() {
+//!       return Err(From::from(ConfigConstructError { errors: __errors }));
+//!     }
+//!     Ok(Self { port, ratio, name })
+//!   }
+//! }
+//! ````
+//!
+//! </details>
+//!
+//! <details><summary>Builder</summary>
+//!
+//! `builder` generates a `{StructName}Builder` alongside the constructor with a setter per argument.
+//! Setters honour the same field options as the constructor; fields with `default` or `val` get
+//! optional setters that override the computed value. Arguments that haven't been set get reported
+//! through the generated `{StructName}ConstructError`.
+//!
+//! ```
+//! # use fancy_constructor::new;
+//! #[derive(new, Debug, PartialEq)]
+//! #[new(builder, args(scale: u8))]
+//! struct Shape {
+//!   #[new(into)]
+//!   name: String,
+//!
+//!   #[new(val(scale * 2))]
+//!   width: u8,
+//!
+//!   #[new(default)]
+//!   tags: Vec<String>,
+//! }
+//!
+//! let shape = Shape::builder().scale(2).name("square").build();
+//! assert_eq!(shape, Ok(Shape::new(2, "square")));
+//!
+//! let shape = Shape::builder().scale(2).name("square").width(1).build().unwrap();
+//! assert_eq!(shape.width, 1);
+//!
+//! let err = Shape::builder().name("square").build().unwrap_err();
+//! assert_eq!(err.to_string(), "invalid `scale`: not set");
+//! ```
+//!
+//! Outputs:
+//!
+#![cfg_attr(doctest, doc = " ````no_test")]
+//! ```
+//! impl Shape {
+//!   pub fn new(scale: u8, name: impl Into<String>) -> Self { /* ... */ }
+//!
+//!   pub fn builder() -> ShapeBuilder {
+//!     Default::default()
+//!   }
+//! }
+//!
+//! #[derive(Default)]
+//! struct ShapeBuilder {
+//!   scale: Option<u8>,
+//!   name: Option<String>,
+//!   width: Option<u8>,
+//!   tags: Option<Vec<String>>,
+//! }
+//!
+//! impl ShapeBuilder {
+//!   pub fn scale(mut self, scale: u8) -> Self {
+//!     self.scale = Some(scale);
+//!     self
+//!   }
+//!
+//!   pub fn name(mut self, name: impl Into<String>) -> Self {
+//!     self.name = Some(name.into());
+//!     self
+//!   }
+//!
+//!   // Same for `width` and `tags`
+//!
+//!   pub fn build(self) -> Result<Shape, ShapeConstructError> {
+//!     let Self { scale, name, width, tags } = self;
+//!     let mut __errors = Vec::new();
+//!     if scale.is_none() {
+//!       __errors.push(("scale", String::from("not set")));
+//!     }
+//!     if name.is_none() {
+//!       __errors.push(("name", String::from("not set")));
+//!     }
+//!     let (Some(scale), Some(name)) = (scale, name) else {
+//!       return Err(From::from(ShapeConstructError { errors: __errors }));
+//!     };
+//!     let __field_width: u8 = match width {
+//!       Some(v) => v,
+//!       None => scale * 2,
+//!     };
+//!     let __field_tags: Vec<String> = match tags {
+//!       Some(v) => v,
+//!       None => Default::default(),
+//!     };
+//!     Ok(Shape { name, width: __field_width, tags: __field_tags })
+//!   }
+//! }
+//! ````
+//!
+//! </details>
+//!
+//! <details><summary>Typestate builder</summary>
+//!
+//! `typestate_builder` generates a `{StructName}Builder` that tracks which arguments have been set
+//! through its type parameters, so `build()` only becomes available once all of them have been.
+//! Fields with `default` or `val` get optional setters that override the computed value.
+//!
+//! ```
+//! # use fancy_constructor::new;
+//! #[derive(new, Debug, PartialEq)]
+//! #[new(typestate_builder)]
+//! struct Shape {
+//!   #[new(into)]
+//!   name: String,
+//!   width: u8,
+//!
+//!   #[new(val(1))]
+//!   height: u8,
+//! }
+//!
+//! let shape = Shape::builder().width(2).name("square").build();
+//! assert_eq!(shape, Shape::new("square", 2));
+//!
+//! let shape = Shape::builder().height(2).name("square").width(2).build();
+//! assert_eq!(shape.height, 2);
+//! ```
+//!
+//! Outputs:
+//!
+#![cfg_attr(doctest, doc = " ````no_test")]
+//! ```
+//! impl Shape {
+//!   pub fn new(name: impl Into<String>, width: u8) -> Self { /* ... */ }
+//!
+//!   pub fn builder() -> ShapeBuilder<(), ()> {
+//!     ShapeBuilder { name: (), width: (), height: None, __marker: PhantomData }
+//!   }
+//! }
+//!
+//! // Unset arguments' type parameters are `()`, set ones' `(T,)`
+//! struct ShapeBuilder<__State0 = (), __State1 = ()> {
+//!   name: __State0,
+//!   width: __State1,
+//!   height: Option<u8>,
+//!   __marker: PhantomData<fn() -> Shape>,
+//! }
+//!
+//! impl<__State0, __State1> ShapeBuilder<__State0, __State1> {
+//!   pub fn height(mut self, height: u8) -> Self {
+//!     self.height = Some(height);
+//!     self
+//!   }
+//! }
+//!
+//! impl<__State1> ShapeBuilder<(), __State1> {
+//!   pub fn name(self, name: impl Into<String>) -> ShapeBuilder<(String,), __State1> {
+//!     ShapeBuilder {
+//!       name: (name.into(),),
+//!       width: self.width,
+//!       height: self.height,
+//!       __marker: PhantomData,
+//!     }
+//!   }
+//! }
+//!
+//! // Same for `width`
+//!
+//! impl ShapeBuilder<(String,), (u8,)> {
+//!   pub fn build(self) -> Shape {
+//!     let Self { name: (name,), width: (width,), height, .. } = self;
+//!     let __field_height: u8 = match height {
+//!       Some(v) => v,
+//!       None => 1,
+//!     };
+//!     Shape { name, width, height: __field_height }
+//!   }
+//! }
+//! ````
+//!
+//! </details>
+//!
+//! <details><summary>Async constructors</summary>
+//!
+//! `async` makes the constructor, as well as any builder's `build()`, an `async fn`, letting `val`
+//! expressions `.await`.
+//!
+//! ```
+//! # use fancy_constructor::new;
+//! # fn block_on<F: core::future::Future>(fut: F) -> F::Output {
+//! #   struct Noop;
+//! #   impl std::task::Wake for Noop { fn wake(self: std::sync::Arc<Self>) {} }
+//! #   let waker = std::sync::Arc::new(Noop).into();
+//! #   let mut cx = std::task::Context::from_waker(&waker);
+//! #   let mut fut = std::pin::pin!(fut);
+//! #   loop {
+//! #     if let std::task::Poll::Ready(v) = fut.as_mut().poll(&mut cx) { return v; }
+//! #   }
+//! # }
+//! async fn lookup_port(host: &str) -> u16 {
+//!   if host == "localhost" { 8080 } else { 443 }
+//! }
+//!
+//! #[derive(new, Debug, PartialEq)]
+//! #[new(async)]
+//! struct Connection {
+//!   #[new(val(lookup_port(&host).await))]
+//!   port: u16,
+//!   host: String,
+//! }
+//!
+//! let conn = block_on(Connection::new("localhost".into()));
+//! assert_eq!(conn, Connection { port: 8080, host: "localhost".into() });
+//! ```
+//!
+//! Outputs:
+//!
+#![cfg_attr(doctest, doc = " ````no_test")]
+//! ```
+//! impl Connection {
+//!   pub async fn new(host: String) -> Self {
+//!     let __field_port: u16 = lookup_port(&host).await;
+//!     Self { port: __field_port, host }
+//!   }
+//! }
+//! ````
+//!
+//! </details>
+//!
+//! <details><summary>Returning smart pointers</summary>
+//!
+//! `returns` wraps the constructed value in a pointer via its `new` fn, or via `pin` for
+//! `Pin<Pointer>`. `Box`, `Rc` & `Arc` don't need to be imported; any other type should be given by
+//! a path that resolves where the struct is declared.
+//!
+//! ```
+//! # use fancy_constructor::new;
+//! # use std::pin::Pin;
+//! # use std::sync::Arc;
+//! #[derive(new)]
+//! #[new(returns(Arc))]
+//! struct Shared {
+//!   value: u8,
+//! }
+//!
+//! #[derive(new)]
+//! #[new(returns(Pin<Box>))]
+//! struct Pinned {
+//!   value: u8,
+//! }
+//!
+//! let shared: Arc<Shared> = Shared::new(1);
+//! let pinned: Pin<Box<Pinned>> = Pinned::new(2);
+//! assert_eq!(shared.value + pinned.value, 3);
+//! ```
+//!
+//! Outputs:
+//!
+#![cfg_attr(doctest, doc = " ````no_test")]
+//! ```
+//! impl Shared {
+//!   pub fn new(value: u8) -> Arc<Self> {
+//!     Arc::new(Self { value })
+//!   }
+//! }
+//!
+//! impl Pinned {
+//!   pub fn new(value: u8) -> Pin<Box<Self>> {
+//!     Box::pin(Self { value })
+//!   }
+//! }
+//! ````
+//!
+//! </details>
+//!
+//! <details><summary>Weak self-references</summary>
+//!
+//! A `weak_self` field makes the constructor build the value through `Arc::new_cyclic`, or the
+//! `returns` pointer's `new_cyclic`, and return the pointer. The field gets the `Weak` handle,
+//! which is also available to `val` expressions as a reference under the field's name.
+//!
+//! ```
+//! # use fancy_constructor::new;
+//! # use std::sync::{Arc, Weak};
+//! #[derive(new)]
+//! struct Child {
+//!   parent: Weak<Parent>,
+//! }
+//!
+//! #[derive(new)]
+//! struct Parent {
+//!   #[new(weak_self)]
+//!   me: Weak<Parent>,
+//!
+//!   #[new(val(vec![Child::new(me.clone())]))]
+//!   children: Vec<Child>,
+//! }
+//!
+//! let parent: Arc<Parent> = Parent::new();
+//! assert!(Arc::ptr_eq(&parent.children[0].parent.upgrade().unwrap(), &parent));
+//! ```
+//!
+//! Outputs:
+//!
+#![cfg_attr(doctest, doc = " ````no_test")]
+//! ```
+//! impl Parent {
+//!   pub fn new() -> Arc<Self> {
+//!     Arc::new_cyclic(move |me| {
+//!       let __field_children: Vec<Child> = vec![Child::new(me.clone())];
+//!       Self { me: Clone::clone(me), children: __field_children }
+//!     })
+//!   }
+//! }
+//! ````
+//!
+//! </details>
+//!
+//! <details><summary>Optional values</summary>
+//!
+//! `some` makes an `Option<T>` field take a `T` argument, while `optional(expr)` makes a `T` field
+//! take an `Option<T>` argument, falling back to the expression if it's `None`.
+//!
+//! ```
+//! # use fancy_constructor::new;
+//! #[derive(new, Debug, PartialEq)]
+//! struct Server {
+//!   #[new(some)]
+//!   name: Option<String>,
+//!
+//!   #[new(optional(8080))]
+//!   port: u16,
+//! }
+//!
+//! let server = Server::new("api".into(), None);
+//! assert_eq!(server, Server { name: Some("api".into()), port: 8080 });
+//! assert_eq!(Server::new("api".into(), Some(80)).port, 80);
+//! ```
+//!
+//! Outputs:
+//!
+#![cfg_attr(doctest, doc = " ````no_test")]
+//! ```
+//! impl Server {
+//!   pub fn new(name: String, port: Option<u16>) -> Self {
+//!     let name: Option<String> = Some(name);
+//!     let port: u16 = match port {
+//!       Some(v) => v,
+//!       None => 8080,
+//!     };
+//!     Self { name, port }
+//!   }
+//! }
+//! ````
+//!
+//! </details>
+//!
+//! <details><summary>Wrapped values</summary>
+//!
+//! `wrap` takes the innermost value as the argument & wraps it in each of the given types via their
+//! `new` fns, outermost first.
+//!
+//! ```
+//! # use fancy_constructor::new;
+//! # use std::sync::{Arc, Mutex};
+//! #[derive(new)]
+//! struct Service {
+//!   #[new(wrap(Arc, Mutex))]
+//!   state: Arc<Mutex<Vec<u8>>>,
+//! }
+//!
+//! let service = Service::new(vec![1, 2]);
+//! assert_eq!(*service.state.lock().unwrap(), [1, 2]);
+//! ```
+//!
+//! Outputs:
+//!
+#![cfg_attr(doctest, doc = " ````no_test")]
+//! ```
+//! impl Service {
+//!   pub fn new(state: Vec<u8>) -> Self {
+//!     let state: Arc<Mutex<Vec<u8>>> = Arc::new(Mutex::new(state));
+//!     Self { state }
+//!   }
+//! }
+//! ````
+//!
+//! </details>
+//!
+//! <details><summary>Trait objects</summary>
+//!
+//! `boxed` makes a `Box<dyn Trait>`, `Rc<dyn Trait>` or `Arc<dyn Trait>` field take an
+//! `impl Trait` argument, which gets `'static` added unless the trait object has a lifetime bound.
+//!
+//! ```
+//! # use fancy_constructor::new;
+//! # use std::sync::Arc;
+//! trait Handler {
+//!   fn handle(&self) -> u8;
+//! }
+//!
+//! struct Fixed;
+//! impl Handler for Fixed {
+//!   fn handle(&self) -> u8 { 1 }
+//! }
+//!
+//! #[derive(new)]
+//! struct Server {
+//!   #[new(boxed)]
+//!   handler: Box<dyn Handler + Send>,
+//!
+//!   #[new(boxed)]
+//!   fallback: Arc<dyn Fn() -> u8 + Send + Sync>,
+//! }
+//!
+//! let server = Server::new(Fixed, || 2);
+//! assert_eq!(server.handler.handle() + (server.fallback)(), 3);
+//! ```
+//!
+//! Outputs:
+//!
+#![cfg_attr(doctest, doc = " ````no_test")]
+//! ```
+//! impl Server {
+//!   pub fn new(
+//!     handler: impl Handler + Send + 'static,
+//!     fallback: impl Fn() -> u8 + Send + Sync + 'static,
+//!   ) -> Self {
+//!     let handler: Box<dyn Handler + Send> = Box::new(handler);
+//!     let fallback: Arc<dyn Fn() -> u8 + Send + Sync> = Arc::new(fallback);
+//!     Self { handler, fallback }
+//!   }
+//! }
+//! ````
+//!
+//! </details>
+//!
+//! <details><summary>Custom argument conversions</summary>
+//!
+//! `arg_ty` sets the argument's type, which then gets converted into the field's type via a `map`
+//! expression, typically a closure, or a `with` fn path. A closure's parameter gets `arg_ty` as its
+//! type if it doesn't have one.
+//!
+//! ```
+//! # use fancy_constructor::new;
+//! # use std::path::{Path, PathBuf};
+//! fn count_words(text: &str) -> usize {
+//!   text.split_whitespace().count()
+//! }
+//!
+//! #[derive(new, Debug, PartialEq)]
+//! struct Document {
+//!   #[new(arg_ty(&Path), map(|p| p.to_path_buf()))]
+//!   path: PathBuf,
+//!
+//!   #[new(arg_ty(&str), with = count_words)]
+//!   words: usize,
+//! }
+//!
+//! let doc = Document::new(Path::new("/tmp/doc"), "foo bar");
+//! assert_eq!(doc, Document { path: PathBuf::from("/tmp/doc"), words: 2 });
+//! ```
+//!
+//! Outputs:
+//!
+#![cfg_attr(doctest, doc = " ````no_test")]
+//! ```
+//! impl Document {
+//!   pub fn new(path: &Path, words: &str) -> Self {
+//!     let path: PathBuf = (|p: &Path| p.to_path_buf())(path);
+//!     let words: usize = count_words(words);
+//!     Self { path, words }
+//!   }
+//! }
+//! ````
+//!
+//! </details>
+//!
+//! <details><summary>Borrowed arguments</summary>
+//!
+//! `borrow` takes the field's borrowed form & converts it via [`ToOwned`](::std::borrow::ToOwned).
+//! The borrowed type gets inferred for `String`, `Vec<T>`, `PathBuf`, `OsString` & `CString`
+//! fields and can be given explicitly via `borrow(Type)`. Adding `as_ref` takes
+//! `impl AsRef<Borrowed>` instead.
+//!
+//! ```
+//! # use fancy_constructor::new;
+//! # use std::path::PathBuf;
+//! #[derive(new, Debug, PartialEq)]
+//! struct Project {
+//!   #[new(borrow)]
+//!   name: String,
+//!
+//!   #[new(borrow)]
+//!   tags: Vec<u8>,
+//!
+//!   #[new(borrow(as_ref))]
+//!   root: PathBuf,
+//! }
+//!
+//! let project = Project::new("foo", &[1, 2], "/tmp/foo");
+//! assert_eq!(project, Project {
+//!   name: "foo".into(),
+//!   tags: vec![1, 2],
+//!   root: PathBuf::from("/tmp/foo"),
+//! });
+//! ```
+//!
+//! Outputs:
+//!
+#![cfg_attr(doctest, doc = " ````no_test")]
+//! ```
+//! impl Project {
+//!   pub fn new(name: &str, tags: &[u8], root: impl AsRef<Path>) -> Self {
+//!     let name: String = ToOwned::to_owned(name);
+//!     let tags: Vec<u8> = ToOwned::to_owned(tags);
+//!     let root: PathBuf = ToOwned::to_owned(AsRef::<Path>::as_ref(&root));
+//!     Self { name, tags, root }
+//!   }
+//! }
+//! ````
+//!
+//! </details>
+//!
+//! <details><summary>Collections</summary>
+//!
+//! `collect` makes the argument `impl IntoIterator` & collects it into the field. The item type is
+//! the collection's 1st generic type, `(K, V)` for types whose name ends with `Map`, or `char` for
+//! `String`. `collect(into)` converts each item via [`Into`](::core::convert::Into) as well - or,
+//! for maps, each key & value separately.
+//!
+//! ```
+//! # use fancy_constructor::new;
+//! # use std::collections::HashMap;
+//! #[derive(new, Debug, PartialEq)]
+//! struct Registry {
+//!   #[new(collect)]
+//!   ids: Vec<u8>,
+//!
+//!   #[new(collect(into))]
+//!   names: Vec<String>,
+//!
+//!   #[new(collect(into))]
+//!   lookup: HashMap<String, u16>,
+//! }
+//!
+//! let registry = Registry::new(1..3, ["a", "b"], [("a", 1u8)]);
+//! assert_eq!(registry, Registry {
+//!   ids: vec![1, 2],
+//!   names: vec!["a".into(), "b".into()],
+//!   lookup: HashMap::from([("a".into(), 1)]),
+//! });
+//! ```
+//!
+//! Outputs:
+//!
+#![cfg_attr(doctest, doc = " ````no_test")]
+//! ```
+//! impl Registry {
+//!   pub fn new(
+//!     ids: impl IntoIterator<Item = u8>,
+//!     names: impl IntoIterator<Item = impl Into<String>>,
+//!     lookup: impl IntoIterator<Item = (impl Into<String>, impl Into<u16>)>,
+//!   ) -> Self {
+//!     let ids: Vec<u8> = Iterator::collect(IntoIterator::into_iter(ids));
+//!     let names: Vec<String> =
+//!       Iterator::collect(Iterator::map(IntoIterator::into_iter(names), Into::into));
+//!     let lookup: HashMap<String, u16> = Iterator::collect(Iterator::map(
+//!       IntoIterator::into_iter(lookup),
+//!       |(k, v)| (Into::<String>::into(k), Into::<u16>::into(v)),
+//!     ));
+//!     Self { ids, names, lookup }
+//!   }
+//! }
+//! ````
+//!
+//! </details>
+//!
+//! <details><summary>Marker fields</summary>
+//!
+//! [`PhantomData`](::core::marker::PhantomData) & [`PhantomPinned`](::core::marker::PhantomPinned)
+//! fields get omitted from the constructor & defaulted automatically. Use `arg` to take them as
+//! arguments anyway.
+//!
+//! ```
+//! # use fancy_constructor::new;
+//! # use std::marker::PhantomData;
+//! #[derive(new)]
+//! struct Id<T> {
+//!   value: u64,
+//!   _marker: PhantomData<T>,
+//! }
+//!
+//! #[derive(new)]
+//! struct Tagged<T> {
+//!   value: u64,
+//!   #[new(arg)]
+//!   tag: PhantomData<T>,
+//! }
+//!
+//! let id = Id::<String>::new(1);
+//! assert_eq!(id.value, 1);
+//!
+//! let tagged = Tagged::<String>::new(2, PhantomData);
+//! assert_eq!(tagged.value, 2);
+//! ```
+//!
+//! Outputs:
+//!
+#![cfg_attr(doctest, doc = " ````no_test")]
+//! ```
+//! impl<T> Id<T> {
+//!   pub fn new(value: u64) -> Self {
+//!     let __field__marker: PhantomData<T> = Default::default();
+//!     Self { value, _marker: __field__marker }
+//!   }
+//! }
+//!
+//! impl<T> Tagged<T> {
+//!   pub fn new(value: u64, tag: PhantomData<T>) -> Self {
+//!     Self { value, tag }
+//!   }
+//! }
+//! ````
+//!
+//! </details>
+//!
+//! <details><summary>Args structs</summary>
+//!
+//! `args_struct` makes the constructor take a single struct holding every argument, which reads
+//! better at call sites than a long list of positional ones. The struct's name defaults to
+//! `{StructName}Args` & its visibility to the container's; both can be changed, and extra traits
+//! derived, via `args_struct(name(Ident), vis(visibility), derive(Trait, ...))`.
+//!
+//! A [`From`](::core::convert::From) implementation gets generated as well, or
+//! [`TryFrom`](::core::convert::TryFrom) if the constructor is fallible. Neither gets generated for
+//! `async` constructors or ones that return a pointer.
+//!
+//! Arguments that aren't a concrete type get held as one they accept, e.g. the field's type for
+//! `into`, `clone` & `borrow` or a `String` for `parse`. `try_into` & `boxed` arguments can't be
+//! held by an args struct.
+//!
+//! ```
+//! # use fancy_constructor::new;
+//! #[derive(new, Debug, PartialEq)]
+//! #[new(args_struct(derive(Clone)), args(scale: u32))]
+//! struct Rect {
+//!   #[new(val(width * scale))]
+//!   area: u32,
+//!   width: u32,
+//!   #[new(into)]
+//!   label: String,
+//! }
+//!
+//! let args = RectArgs { scale: 2, width: 5, label: "wide".into() };
+//! let rect = Rect::new(args.clone());
+//! assert_eq!(rect, Rect { area: 10, width: 5, label: "wide".into() });
+//! assert_eq!(Rect::from(args), rect);
+//! ```
+//!
+//! Outputs:
+//!
+#![cfg_attr(doctest, doc = " ````no_test")]
+//! ```
+//! impl Rect {
+//!   pub fn new(args: RectArgs) -> Self {
+//!     let RectArgs { scale, width, label } = args;
+//!     let label: String = label.into();
+//!     let __field_area: u32 = width * scale;
+//!     Self { area: __field_area, width, label }
+//!   }
+//! }
+//!
+//! /// Arguments for constructing a [`Rect`].
+//! #[derive(Clone)]
+//! struct RectArgs {
+//!   /// The `scale` argument.
+//!   scale: u32,
+//!   /// The `width` argument.
+//!   width: u32,
+//!   /// The `label` argument.
+//!   label: String,
+//! }
+//!
+//! impl From<RectArgs> for Rect {
+//!   fn from(args: RectArgs) -> Self {
+//!     Self::new(args)
+//!   }
+//! }
+//! ````
+//!
+//! </details>
+//!
+//! <details><summary>Deserialization</summary>
+//!
+//! With the `serde` feature enabled, `deserialize` implements
+//! [`Deserialize`](https://docs.rs/serde/latest/serde/trait.Deserialize.html) by deserializing
+//! the constructor's arguments & calling it, so computed fields & validation apply to
+//! deserialized values too. A fallible constructor's errors get converted via
+//! [`Error::custom`](https://docs.rs/serde/latest/serde/de/trait.Error.html#tymethod.custom).
+//! Arguments that aren't a concrete type get deserialized as one they accept, e.g. the field's
+//! type for `into`, `clone` & `borrow` or a `String` for `parse`.
+//!
+#![cfg_attr(all(doctest, not(feature = "serde")), doc = " ````ignore")]
+//! ```
+//! # use fancy_constructor::new;
+//! #[derive(new, Debug)]
+//! #[new(deserialize, validate(check_range), error(String))]
+//! struct Range {
+//!   #[new(into)]
+//!   name: String,
+//!   start: i32,
+//!   end: i32,
+//!   #[new(val(end - start))]
+//!   len: i32,
+//! }
+//!
+//! fn check_range(range: &Range) -> Result<(), String> {
+//!   if range.start <= range.end {
+//!     Ok(())
+//!   } else {
+//!     Err(format!("{} starts after it ends", range.name))
+//!   }
+//! }
+//!
+//! let range: Range = serde_json::from_str(r#"{"name": "a", "start": 1, "end": 4}"#).unwrap();
+//! assert_eq!(range.len, 3);
+//!
+//! let err = serde_json::from_str::<Range>(r#"{"name": "b", "start": 4, "end": 1}"#).unwrap_err();
+//! assert_eq!(err.to_string(), "b starts after it ends");
+//! ````
+//!
+//! Outputs:
+//!
+#![cfg_attr(doctest, doc = " ````no_test")]
+//! ```
+//! const _: () = {
+//!   #[derive(serde::Deserialize)]
+//!   #[serde(rename = "Range")]
+//!   struct __RangeArgs {
+//!     name: String,
+//!     start: i32,
+//!     end: i32,
+//!   }
+//!
+//!   impl<'de> serde::Deserialize<'de> for Range {
+//!     fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+//!       let __RangeArgs { name, start, end } = __RangeArgs::deserialize(deserializer)?;
+//!       Self::try_new(name, start, end).map_err(serde::de::Error::custom)
+//!     }
+//!   }
+//! };
+//! ````
+//!
+//! </details>
+//!
+//! <details><summary>Setup statements</summary>
+//!
+//! `setup { ... }` runs statements in the constructor body before any value is computed. Whatever
+//! they bind is visible to every `val` expression, and they can use the constructor's arguments,
+//! so shared work only needs doing once. On enums, every variant's constructor runs them, and bindings a
+//! variant doesn't use don't trigger `unused_variables` warnings.
+//!
+//! ```
+//! # use fancy_constructor::new;
+//! #[derive(new)]
+//! #[new(args(input: &str), setup {
+//!   let (key, value) = input.split_once('=').unwrap_or((input, ""));
+//! })]
+//! struct Entry {
+//!   #[new(val(key.trim().to_owned()))]
+//!   key: String,
+//!   #[new(val(value.trim().to_owned()))]
+//!   value: String,
+//! }
+//!
+//! let entry = Entry::new("name = foo");
+//! assert_eq!(entry.key, "name");
+//! assert_eq!(entry.value, "foo");
+//! ```
+//!
+//! Outputs:
+//!
+#![cfg_attr(doctest, doc = " ````no_test")]
+//! ```
+//! impl Entry {
+//!   pub fn new(input: &str) -> Self {
+//!     let (key, value) = input.split_once('=').unwrap_or((input, ""));
+//!     let __field_key: String = key.trim().to_owned();
+//!     let __field_value: String = value.trim().to_owned();
+//!     Self { key: __field_key, value: __field_value }
+//!   }
+//! }
+//! ````
+//!
+//! </details>
+//!
+//! <details><summary>Post-construction hooks</summary>
+//!
+//! `post(path::to_fn)` calls the fn with `&mut Self` once the value's been built, before it gets
+//! returned, e.g. to compute caches. `post(map(path::to_fn))` passes the value by value & returns
+//! whatever the fn returns instead, while `post(try_map(path::to_fn))` expects a
+//! `Result<Self, E>`, making the constructor fallible with its error converted into the `error`
+//! type. Hooks apply to builders too.
+//!
+//! ```
+//! # use fancy_constructor::new;
+//! #[derive(new)]
+//! #[new(post(Self::index))]
+//! struct Document {
+//!   text: String,
+//!   #[new(default)]
+//!   word_count: usize,
+//! }
+//!
+//! impl Document {
+//!   fn index(&mut self) {
+//!     self.word_count = self.text.split_whitespace().count();
+//!   }
+//! }
+//!
+//! #[derive(new, Debug)]
+//! #[new(post(try_map(non_empty)), error(String))]
+//! struct Name(String);
+//!
+//! fn non_empty(name: Name) -> Result<Name, &'static str> {
+//!   if name.0.is_empty() { Err("empty name") } else { Ok(name) }
+//! }
+//!
+//! assert_eq!(Document::new("foo bar".into()).word_count, 2);
+//! assert_eq!(Name::try_new(String::new()).unwrap_err(), "empty name");
+//! ```
+//!
+//! Outputs:
+//!
+#![cfg_attr(doctest, doc = " ````no_test")]
+//! ```
+//! impl Document {
+//!   pub fn new(text: String) -> Self {
+//!     let __field_word_count: usize = Default::default();
+//!     let mut __constructed = Self { text, word_count: __field_word_count };
+//!     Self::index(&mut __constructed);
+//!     __constructed
+//!   }
+//! }
+//!
+//! impl Name {
+//!   pub fn try_new(f1: String) -> Result<Self, String> {
+//!     Ok(non_empty(Self(f1))?)
+//!   }
+//! }
+//! ````
+//!
+//! </details>
+//!
+//! <details><summary>Unsafe constructors</summary>
+//!
+//! `unsafe("...")` makes the constructor an `unsafe fn`, for types with invariants the compiler
+//! can't check. The string is required & becomes the constructor's `# Safety` doc section.
+//!
+//! `unchecked` additionally generates an `unsafe fn {name}_unchecked` from the same configuration,
+//! skipping the container's & fields' validators, so the two constructors can't drift apart.
+//! `unchecked("...")` replaces the default `# Safety` section it gets.
+//!
+//! ```
+//! # use fancy_constructor::new;
+//! # use std::ptr::NonNull;
+//! #[derive(new)]
+//! #[new(unsafe("`ptr` must be valid for reads of `len` bytes."))]
+//! struct RawBuf {
+//!   ptr: NonNull<u8>,
+//!   len: usize,
+//! }
+//!
+//! #[derive(new, Debug)]
+//! #[new(validate(Self::check), error(&'static str), unchecked)]
+//! struct Span {
+//!   start: usize,
+//!   end: usize,
+//! }
+//!
+//! impl Span {
+//!   fn check(&self) -> Result<(), &'static str> {
+//!     if self.start <= self.end { Ok(()) } else { Err("start after end") }
+//!   }
+//! }
+//!
+//! let mut bytes = [1u8, 2];
+//! let buf = unsafe { RawBuf::new(NonNull::from(&mut bytes[0]), bytes.len()) };
+//! assert_eq!(buf.len, 2);
+//!
+//! assert!(Span::try_new(2, 1).is_err());
+//! let span = unsafe { Span::new_unchecked(1, 2) };
+//! assert_eq!(span.end, 2);
+//! ```
+//!
+//! Outputs:
+//!
+#![cfg_attr(doctest, doc = " ````no_test")]
+//! ```
+//! impl RawBuf {
+//!   /// Constructs a new instance of the struct.
+//!   ///
+//!   /// # Safety
+//!   ///
+//!   /// `ptr` must be valid for reads of `len` bytes.
+//!   pub unsafe fn new(ptr: NonNull<u8>, len: usize) -> Self {
+//!     Self { ptr, len }
+//!   }
+//! }
+//!
+//! impl Span {
+//!   pub fn try_new(start: usize, end: usize) -> Result<Self, &'static str> {
+//!     let __constructed = Self { start, end };
+//!     (Self::check)(&__constructed)?;
+//!     Ok(__constructed)
+//!   }
+//!
+//!   /// Constructs a new instance of the struct.
+//!   ///
+//!   /// Skips validating the arguments & the constructed value.
+//!   ///
+//!   /// # Safety
+//!   ///
+//!   /// The arguments & the constructed value must pass the validation this constructor skips.
+//!   pub unsafe fn new_unchecked(start: usize, end: usize) -> Self {
+//!     Self { start, end }
+//!   }
+//! }
+//! ````
+//!
+//! </details>
+//!
+//! <details><summary>Invalid inputs</summary>
+//!
+//! ```compile_fail
+//! #[derive(fancy_constructor::new)]
+//! enum Foo {
+//!   Bar, // no variants marked with `#[new]`
+//! }
+//! ```
+//!
+//! ```compile_fail
+//! #[derive(fancy_constructor::new)]
+//! #[new(default)]
+//! enum Foo {
+//!   #[new] Bar, // `default` can't pick between multiple constructors
+//!   #[new] Qux,
+//! }
+//! ```
+//!
+//! ```compile_fail
+//! #[derive(fancy_constructor::new)]
+//! #[new(validate(|_: &Self| Ok::<(), String>(())))] // no `error` type
+//! struct Foo(u8);
+//! ```
+//!
+//! ```compile_fail
+//! #[derive(fancy_constructor::new)]
+//! #[new(validate(|_: &Self| Ok::<(), String>(())))] // field validators don't make `error` optional
+//! struct Foo(#[new(validate(true))] u8);
+//! ```
+//!
+//! ```compile_fail
+//! #[derive(fancy_constructor::new)]
+//! #[new(validate(|_: &Self| Ok::<_, String>(())), error(String), const_fn)] // fallible constructors can't be `const_fn`
+//! struct Foo(u8);
+//! ```
+//!
+//! ```compile_fail
+//! #[derive(fancy_constructor::new)]
+//! struct Foo {
+//!   #[new(val(1), validate(bar > 0))] // `bar` isn't an argument
+//!   bar: u8,
+//! }
+//! ```
+//!
+//! ```compile_fail
+//! #[derive(fancy_constructor::new)]
+//! struct Foo( // multiple `try_into` fields without an `error` type
+//!   #[new(try_into)] u8,
+//!   #[new(try_into)] i8,
+//! );
+//! ```
+//!
+//! ```compile_fail
+//! #[derive(fancy_constructor::new)]
+//! #[new(builder)] // Builders are only supported on structs
+//! enum Foo {
+//!   #[new] Bar(u8),
+//! }
+//! ```
+//!
+//! ```compile_fail
+//! #[derive(fancy_constructor::new)]
+//! #[new(typestate_builder)]
+//! struct Foo {
+//!   bar: u8,
+//! }
+//!
+//! Foo::builder().build(); // `bar` hasn't been set
+//! ```
+//!
+//! ```compile_fail
+//! #[derive(fancy_constructor::new)]
+//! #[new(async, const_fn)] // `async` constructors can't be `const fn`
+//! struct Foo {
+//!   bar: u8,
+//! }
+//! ```
+//!
+//! ```compile_fail
+//! #[derive(fancy_constructor::new)]
+//! #[new(returns(Box), default)] // `Default` needs the constructor to return `Self`
+//! struct Foo {
+//!   #[new(default)]
+//!   bar: u8,
+//! }
+//! ```
+//!
+//! ```compile_fail
+//! # use std::sync::Weak;
+//! #[derive(fancy_constructor::new)]
+//! struct Foo {
+//!   #[new(weak_self, default)] // `weak_self` fields get initialised by the constructor
+//!   me: Weak<Foo>,
+//! }
+//! ```
+//!
+//! ```compile_fail
+//! #[derive(fancy_constructor::new)]
+//! struct Foo {
+//!   #[new(some)] // `some` requires an `Option<T>` field
+//!   bar: u8,
+//! }
+//! ```
+//!
+//! ```compile_fail
+//! # use std::sync::{Arc, Mutex};
+//! #[derive(fancy_constructor::new)]
+//! struct Foo {
+//!   #[new(wrap(Arc, Mutex))] // The type isn't nested deep enough
+//!   bar: Arc<u8>,
+//! }
+//! ```
+//!
+//! ```compile_fail
+//! #[derive(fancy_constructor::new)]
+//! struct Foo {
+//!   #[new(boxed)] // Not a trait object
+//!   bar: Box<u8>,
+//! }
+//! ```
+//!
+//! ```compile_fail
+//! #[derive(fancy_constructor::new)]
+//! struct Foo {
+//!   #[new(arg_ty(u8))] // No `map` or `with` to convert the argument
+//!   bar: u16,
+//! }
+//! ```
+//!
+//! ```compile_fail
+//! #[derive(fancy_constructor::new)]
+//! struct Foo {
+//!   #[new(borrow)] // Can't infer the borrowed type
+//!   bar: u8,
+//! }
+//! ```
+//!
+//! ```compile_fail
+//! #[derive(fancy_constructor::new)]
+//! struct Foo {
+//!   #[new(collect)] // Not a collection
+//!   bar: u8,
+//! }
+//! ```
+//!
+//! ```compile_fail
+//! #[derive(fancy_constructor::new)]
+//! struct Foo {
+//!   #[new(into, collect)] // Pick one: `collect(into)` converts the items
+//!   bar: Vec<u8>,
+//! }
+//! ```
+//!
+//! ```compile_fail
+//! #[derive(fancy_constructor::new)]
+//! struct Foo {
+//!   #[new(arg, default)] // Can't be both an argument & defaulted
+//!   bar: std::marker::PhantomPinned,
+//! }
+//! ```
+//!
+//! ```compile_fail
+//! #[derive(fancy_constructor::new)]
+//! #[new(args_struct)]
+//! struct Foo {
+//!   #[new(try_into)] // No concrete type to hold
+//!   bar: u8,
+//! }
+//! ```
+//!
+#![cfg_attr(all(doctest, not(feature = "serde")), doc = " ````ignore")]
+//! ```compile_fail
+//! #[derive(fancy_constructor::new)]
+//! #[new(deserialize, error(String))]
+//! struct Foo {
+//!   #[new(try_into)] // No concrete type to deserialize
+//!   bar: u8,
+//! }
+//! ````
+//!
+//! ```compile_fail
+//! #[derive(fancy_constructor::new)]
+//! struct Foo {
+//!   #[new(val(b + 1))] // `a` & `b` reference each other
+//!   a: u8,
+//!   #[new(val(a + 1))]
+//!   b: u8,
+//! }
+//! ```
+//!
+//! ```compile_fail
+//! #[derive(fancy_constructor::new)]
+//! #[new(setup)] // Expected a block of statements
+//! struct Foo {
+//!   bar: u8,
+//! }
+//! ```
+//!
+//! ```compile_fail
+//! #[derive(fancy_constructor::new)]
+//! #[new(post(try_map(check)))] // Requires an `error` type
+//! struct Foo {
+//!   bar: u8,
+//! }
+//! # fn check(foo: Foo) -> Result<Foo, String> { Ok(foo) }
+//! ```
+//!
+//! ```compile_fail
+//! #[derive(fancy_constructor::new)]
+//! #[new(unsafe)] // Requires a `# Safety` doc
+//! struct Foo {
+//!   bar: u8,
+//! }
+//! ```
+//!
+//! ```compile_fail
+//! #[derive(fancy_constructor::new)]
+//! #[new(unchecked)] // Nothing to skip
+//! struct Foo {
+//!   bar: u8,
+//! }
+//! ```
+//!
+//! ```compile_fail
+//! #[derive(fancy_constructor::new)]
+//! #[new(builder)] // `try_into` errors can't convert into the builder's error type
+//! struct Foo {
+//!   #[new(try_into)]
+//!   bar: u8,
+//! }
+//! ```
+//!
+//! ```compile_fail
+//! # use std::sync::Weak;
+//! #[derive(fancy_constructor::new)]
+//! #[new(returns(Box))] // `Box` has no `new_cyclic`
+//! struct Foo {
+//!   #[new(weak_self)]
+//!   me: Weak<Foo>,
+//! }
+//! ```
+//!
+//! ```compile_fail
+//! #[derive(fancy_constructor::new)]
+//! union Foo { // Unions not supported
+//!   bar: u8,
+//!   qux: u8,
+//! }
+//! ```
+//!
+//! </details>
+
+#![deny(clippy::correctness, clippy::suspicious)]
+#![warn(clippy::complexity, clippy::perf, clippy::style, clippy::pedantic)]
+#![allow(clippy::needless_pass_by_value)]
+#![warn(missing_docs)]
+
+mod options;
+mod parse;
+mod tokenise;
+mod types;
+
+extern crate proc_macro;
+
+use crate::types::FieldsSource;
+use proc_macro2::Ident;
+use syn::{Attribute, Type};
+
+const ATTR_NAME: &str = "new";
+
+/// See [crate-level docs](crate) for a usage and output example.
+///
+/// # Container options
+///
+/// | Opt | Default | Description |
+/// | --- | --- | --- |
+/// | `new(const_fn)` | `false` | Whether to make the constructor a `const fn` |
+/// | `default` | `false` | Generate a [`Default`](::core::default::Default) implementation if the constructor ends up with no arguments. |
+/// | `new(vis(visibility))` | `pub` | The visibility of the constructor |
+/// | `new(name(ident))` | `new` | Constructor fn name |
+/// | `new(comment(literal))` | | A doc comment to add to the constructor |
+/// | `new(bounds(T: Whatever))` | | Generic type bounds for the implementation |
+/// | `new(args(foo: u8, bar: String))` | | Additional arguments to add to the **beginning** of the args list |
+/// | `new(validate(path::to_fn))` | | Call this fn with a reference to the constructed value, making the constructor fallible & named `try_{name}`. It should return `Result<(), E>`. Requires an `error` type implementing `From<E>`, even if fields have validators. |
+/// | `new(error(Type))` | `{StructName}ConstructError` if any fields have validators or get parsed, or with `builder`; the conversion error of a sole `try_into` field | The error type of a fallible constructor. Field validation & conversion errors get converted into it via [`From`](::core::convert::From), as does `{StructName}ConstructError` when it's generated, so the type must implement `From<{StructName}ConstructError>` if fields get checked or `builder` is set. |
+/// | `new(panicking)` | `false` | Additionally generate an infallible `{name}` constructor that panics if the fallible one fails |
+/// | `new(builder)` | `false` | Structs only: additionally generate a `{StructName}Builder` with a setter per argument. Fields with `default` or `val` get setters overriding the computed value. Unset arguments get reported through `{StructName}ConstructError`, so a custom `error` type must implement `From<{StructName}ConstructError>` & `try_into` fields require one. |
+/// | `new(typestate_builder)` | `false` | Structs only: like `builder`, but `build()` only exists once every argument has been set. Mutually exclusive with `builder`. |
+/// | `new(async)` | `false` | Make the constructor, as well as any builder's `build()`, an `async fn`. Incompatible with `const_fn` & `default`. |
+/// | `new(returns(Pointer))` | | Wrap the constructed value in a pointer with a `new` fn, e.g. `Arc`, or `Pin<Pointer>`, e.g. `Pin<Box>`. Also applies to builders. Incompatible with `const_fn` & `default`. |
+/// | `new(args_struct)` | `false` | Structs only: make the constructor take a `{StructName}Args` struct holding every argument & implement `From` (or `TryFrom`) for it. Configurable via `args_struct(name(Ident), vis(visibility), derive(Trait, ...))`. Incompatible with `default`, `try_into` & `boxed` arguments & `arg_ty`s that are `impl Trait` or have elided lifetimes. |
+/// | `new(deserialize)` | `false` | Requires the `serde` feature. Structs only: implement `serde::Deserialize` by deserializing the constructor's arguments & calling it. Incompatible with `async`, `returns`, `weak_self`, `try_into` & `boxed`. |
+/// | `new(setup { stmts })` | | Statements to run before any field's value gets computed. They can use the arguments & whatever they bind is visible to `val` expressions. |
+/// | `new(post(path::to_fn))` | | Call the fn with `&mut Self` before returning the constructed value. `post(map(path))` passes `Self` & returns the result; `post(try_map(path))` expects a `Result<Self, E>`, making the constructor fallible, & requires an `error` type `E` converts into. |
+/// | `new(unsafe(literal))` | | Make the constructor an `unsafe fn` with the literal as its `# Safety` doc section. Can't be combined with `default`, builders or `deserialize`; args structs don't get a conversion impl. |
+/// | `new(unchecked)` | | Additionally generate an `unsafe fn {name}_unchecked` that skips the container's & fields' validators. `unchecked(literal)` sets its `# Safety` doc section. Requires a validator & can't be combined with `parse`, `try_into` or `post(try_map)`. |
+/// | `new(all_variants)` | `false` | Enums only: generate a constructor for every variant, not just ones marked with `#[new]` |
+///
+/// # Variant options
+///
+/// | Opt | Default | Description |
+/// | --- | --- | --- |
+/// | `new` | | Generate a constructor for this variant |
+/// | `new(name(ident))` | `new` or `new_variant_name` | Constructor fn name. The variant's snake-cased name gets appended to the container's constructor name if multiple constructors are generated. |
+/// | `new(const_fn)` | container's | Whether to make the constructor a `const fn` |
+/// | `new(vis(visibility))` | container's | The visibility of the constructor |
+/// | `new(comment(literal))` | container's | A doc comment to add to the constructor |
+/// | `new(bounds(T: Whatever))` | | Generic type bounds appended to the container's |
+/// | `new(args(foo: u8, bar: String))` | | Additional arguments appended to the container's |
 ///
 /// # Field options
 ///
@@ -293,9 +1850,23 @@ const ATTR_NAME: &str = "new";
 /// | --- | --- |
 /// | `new(default)` | Omit the field from the constructor and use [`Default`](::core::default::Default) |
 /// | `new(clone)` | Make the argument pass-by-reference and clone it |
+/// | `new(borrow)` | Make the argument the field type's borrowed form, e.g. `&str` for `String`, & convert it via [`ToOwned`](::std::borrow::ToOwned). Use `borrow(Type)` to set the borrowed type & `borrow(as_ref)` to take `impl AsRef<Borrowed>`. |
 /// | `new(into)` | Make the argument [`Into<T>`](::core::convert::Into) |
+/// | `new(try_into)` | Make the argument [`TryInto<T>`](::core::convert::TryInto), making the constructor fallible |
+/// | `new(parse)` | Make the argument `impl AsRef<str>` & parse it via [`FromStr`](::core::str::FromStr), making the constructor fallible |
+/// | `new(some)` | Make an `Option<T>` field's argument `T` & wrap it in `Some` |
+/// | `new(optional(expr))` | Make the argument `Option<T>`, falling back to the expression if it's `None` |
+/// | `new(wrap(Outer, Inner))` | Make the argument the innermost type & wrap it via each type's `new` fn, e.g. `Arc<Mutex<T>>` for `wrap(Arc, Mutex)` |
+/// | `new(boxed)` | Make a `Box<dyn Trait>`-like field's argument `impl Trait` & wrap it via the pointer's `new` fn |
+/// | `new(arg_ty(Type))` | Set the argument's type; requires `map` or `with` |
+/// | `new(map(expr))` | Convert the `arg_ty` argument by calling the expression, e.g. a closure, with it |
+/// | `new(with = path::to_fn)` | Convert the `arg_ty` argument by calling the given fn with it |
+/// | `new(collect)` | Make the argument `impl IntoIterator` & collect it. Use `collect(into)` to convert each item - each key & value for maps - via [`Into`](::core::convert::Into). Can't be combined with other conversions. |
+/// | `new(arg)` | Take a `PhantomData` or `PhantomPinned` field as an argument instead of defaulting it |
 /// | `new(name(ident))` | Rename the function argument - useful for newtype structs |
-/// | `new(val(expr))` | Initialise the value with the following expression instead of a constructor argument |
+/// | `new(val(expr))` | Initialise the value with the following expression instead of a constructor argument. It can reference the converted arguments & other fields' computed values by name. |
+/// | `new(validate(expr_or_fn))` | Validate the argument, making the constructor fallible. Paths & closures get called with a reference to the argument; other expressions should evaluate to a `bool`. |
+/// | `new(weak_self)` | Initialise the field with the constructed value's `Weak` handle, making the constructor return an `Arc` (or the `returns` pointer, which must be an `Arc` or `Rc`) built via `new_cyclic` |
 ///
 #[proc_macro_derive(new, attributes(new))]
 pub fn derive_fancy_constructor(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
@@ -308,6 +1879,7 @@ type FmtTuple = (Vec<Attribute>, Result<Ident, Ident>, Type);
 
 struct FancyConstructor {
     struct_name: Ident,
+    vis: syn::Visibility,
     generics: syn::Generics,
     fields: FieldsSource,
     opts: options::ContainerOptions,
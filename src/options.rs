@@ -1,13 +1,47 @@
-use crate::types::MiniField;
+use crate::types::{
+    borrowed_ty, ArgsStructOptions, BorrowOptions, CollectOptions, Fields, MiniField, PostHook,
+    ReturnWrapper, SafetyDoc, SetupBlock,
+};
 use macroific::prelude::*;
-use proc_macro2::Ident;
+use proc_macro2::{Ident, TokenStream};
+use quote::ToTokens;
 use syn::punctuated::Punctuated;
-use syn::{Expr, LitStr, Token, TypeParam, Visibility};
+use syn::{Expr, LitStr, Path, Token, Type, TypeParam, Visibility};
 
-#[derive(AttributeOptions, Default)]
+#[derive(AttributeOptions, Default, Clone)]
+#[allow(clippy::struct_excessive_bools)]
 pub struct ContainerOptions {
     pub const_fn: bool,
     pub default: bool,
+    pub all_variants: bool,
+    pub vis: Option<Visibility>,
+    pub name: Option<Ident>,
+    pub comment: Option<LitStr>,
+    pub bounds: Punctuated<TypeParam, Token![,]>,
+    pub args: Punctuated<MiniField, Token![,]>,
+    pub validate: Option<Expr>,
+    pub error: Option<Type>,
+    pub panicking: bool,
+    pub builder: bool,
+    pub typestate_builder: bool,
+
+    #[attr_opts(rename = "async")]
+    pub is_async: bool,
+    pub returns: Option<ReturnWrapper>,
+    pub args_struct: Option<ArgsStructOptions>,
+    pub deserialize: bool,
+    pub setup: Option<SetupBlock>,
+    pub post: Option<PostHook>,
+
+    #[attr_opts(rename = "unsafe")]
+    pub is_unsafe: Option<SafetyDoc>,
+    pub unchecked: Option<SafetyDoc>,
+}
+
+/// Variant-level overrides for [`ContainerOptions`]
+#[derive(AttributeOptions, Default)]
+pub struct VariantOptions {
+    pub const_fn: Option<bool>,
     pub vis: Option<Visibility>,
     pub name: Option<Ident>,
     pub comment: Option<LitStr>,
@@ -15,25 +49,168 @@ pub struct ContainerOptions {
     pub args: Punctuated<MiniField, Token![,]>,
 }
 
-#[derive(AttributeOptions)]
+impl ContainerOptions {
+    /// Whether the constructor for the given fields returns a [`Result`]
+    #[inline]
+    pub fn is_fallible(&self, fields: &Fields) -> bool {
+        self.checks_value() || fields.is_fallible()
+    }
+
+    /// Whether the constructed value gets checked by `validate` or a fallible `post` hook
+    #[inline]
+    pub fn checks_value(&self) -> bool {
+        self.validate.is_some() || self.post.as_ref().is_some_and(PostHook::is_fallible)
+    }
+
+    /// Apply a variant's options on top of these. Bounds & args get appended, everything else
+    /// gets overridden.
+    pub fn with_variant(&self, variant: VariantOptions) -> Self {
+        let VariantOptions {
+            const_fn,
+            vis,
+            name,
+            comment,
+            bounds,
+            args,
+        } = variant;
+
+        let mut out = self.clone();
+
+        if let Some(const_fn) = const_fn {
+            out.const_fn = const_fn;
+        }
+        if vis.is_some() {
+            out.vis = vis;
+        }
+        if name.is_some() {
+            out.name = name;
+        }
+        if comment.is_some() {
+            out.comment = comment;
+        }
+
+        out.bounds.extend(bounds);
+        out.args.extend(args);
+
+        out
+    }
+}
+
+#[derive(AttributeOptions, Clone)]
+#[allow(clippy::struct_excessive_bools)]
 pub struct FieldOptions {
     pub default: bool,
     pub clone: bool,
     pub into: bool,
+    pub try_into: bool,
+    pub parse: bool,
     pub name: Option<Ident>,
 
     #[attr_opts(rename = "val")]
     pub value: Option<Expr>,
+
+    pub validate: Option<Expr>,
+    pub weak_self: bool,
+    pub some: bool,
+    pub optional: Option<Expr>,
+    pub wrap: Punctuated<Path, Token![,]>,
+    pub boxed: bool,
+    pub arg_ty: Option<Type>,
+    pub map: Option<Expr>,
+    pub with: Option<Path>,
+    pub borrow: Option<BorrowOptions>,
+    pub collect: Option<CollectOptions>,
+    pub arg: bool,
+}
+
+/// How an argument that isn't taken by value gets passed
+pub enum ArgReference {
+    /// By value
+    None,
+
+    /// As `&T`, getting cloned
+    Clone,
+
+    /// As `&Borrowed` or `impl AsRef<Borrowed>`, getting converted via `ToOwned`
+    Borrow { borrowed: TokenStream, as_ref: bool },
 }
 
 impl FieldOptions {
+    /// How the argument gets passed given the field's type. Falls back to [`ArgReference::None`]
+    /// if the borrowed type can't be inferred, which gets rejected when the options are parsed.
+    pub fn reference(&self, ty: &Type) -> ArgReference {
+        if self.clone {
+            return ArgReference::Clone;
+        }
+
+        let Some(ref borrow) = self.borrow else {
+            return ArgReference::None;
+        };
+        let borrowed = match borrow.ty {
+            Some(ref borrowed) => borrowed.to_token_stream(),
+            None => match borrowed_ty(ty) {
+                Some(borrowed) => borrowed,
+                None => return ArgReference::None,
+            },
+        };
+
+        ArgReference::Borrow {
+            borrowed,
+            as_ref: borrow.as_ref,
+        }
+    }
+
+    /// Whether the argument needs converting before it can be assigned to the field
     #[inline]
-    pub fn uses_reference(&self) -> bool {
+    pub fn is_converted(&self) -> bool {
         self.clone
+            || self.into
+            || self.try_into
+            || self.some
+            || self.optional.is_some()
+            || !self.wrap.is_empty()
+            || self.boxed
+            || self.map.is_some()
+            || self.with.is_some()
+            || self.borrow.is_some()
+            || self.collect.is_some()
+    }
+
+    /// Names of the options that convert the argument into the field's value
+    pub fn conversions(&self) -> Vec<&'static str> {
+        [
+            ("clone", self.clone),
+            ("into", self.into),
+            ("try_into", self.try_into),
+            ("parse", self.parse),
+            ("some", self.some),
+            ("optional", self.optional.is_some()),
+            ("wrap", !self.wrap.is_empty()),
+            ("boxed", self.boxed),
+            ("map", self.map.is_some()),
+            ("with", self.with.is_some()),
+            ("borrow", self.borrow.is_some()),
+            ("collect", self.collect.is_some()),
+        ]
+        .into_iter()
+        .filter_map(move |(name, set)| set.then_some(name))
+        .collect()
+    }
+
+    /// Whether initialising the field can fail
+    #[inline]
+    pub fn is_fallible(&self) -> bool {
+        self.try_into || self.is_checked()
+    }
+
+    /// Whether the argument gets checked & converted ahead of construction, collecting errors
+    #[inline]
+    pub fn is_checked(&self) -> bool {
+        self.parse || self.validate.is_some()
     }
 
     #[inline]
     pub fn should_skip_args(&self) -> bool {
-        self.default || self.value.is_some()
+        self.default || self.value.is_some() || self.weak_self
     }
 }
@@ -3,10 +3,10 @@ use proc_macro2::Span;
 use syn::parse::{Parse, ParseStream};
 use syn::punctuated::Punctuated;
 use syn::spanned::Spanned;
-use syn::{Data, DeriveInput, Error, Variant};
+use syn::{Data, DeriveInput, Error, GenericArgument, Meta, PathArguments, Type, Variant};
 
-use crate::options::ContainerOptions;
-use crate::types::FieldsSource;
+use crate::options::{ContainerOptions, VariantOptions};
+use crate::types::{EnumVariant, Fields, FieldsSource, PostHook};
 use crate::{FancyConstructor, ATTR_NAME};
 
 impl Parse for FancyConstructor {
@@ -16,46 +16,476 @@ impl Parse for FancyConstructor {
             attrs,
             generics,
             data,
-            vis: _,
+            vis,
         } = input.parse()?;
 
+        let span = create_span(&attrs);
+        let opts = if attrs.is_empty() {
+            ContainerOptions::default()
+        } else {
+            ContainerOptions::from_iter_named(ATTR_NAME, span, attrs)?
+        };
+
         let fields = match data {
             Data::Struct(s) => FieldsSource::Struct(s.fields.try_into()?),
-            Data::Enum(e) => {
-                let variant = find_variant(&e.enum_token, e.variants)?;
-
-                FieldsSource::Enum {
-                    fields: variant.fields.try_into()?,
-                    variant: variant.ident,
-                }
-            }
+            Data::Enum(e) => FieldsSource::Enum(collect_variants(
+                &e.enum_token,
+                e.variants,
+                opts.all_variants,
+            )?),
             Data::Union(u) => {
                 return Err(Error::new_spanned(u.union_token, "Unions not supported"));
             }
         };
 
-        Ok(Self {
-            opts: if attrs.is_empty() {
-                ContainerOptions::default()
-            } else {
-                let span = create_span(&attrs);
-                let opts = ContainerOptions::from_iter_named(ATTR_NAME, span, attrs)?;
-
-                if opts.default {
-                    validate_opts(&opts, &fields, span)?;
-                }
+        validate_fallible(&opts, &fields, span)?;
+        if opts.is_async {
+            validate_async(&opts, &fields, span)?;
+        }
+        if opts.returns.is_some() {
+            validate_returns(&opts, &fields, span)?;
+        }
+        validate_weak_self(&opts, &fields, span)?;
+        if opts.builder || opts.typestate_builder {
+            validate_builder(&opts, &fields, span)?;
+        }
+        if opts.default {
+            validate_default(&opts, &fields, span)?;
+        }
+        if opts.args_struct.is_some() {
+            validate_args_struct(&opts, &fields, span)?;
+        }
+        if opts.deserialize {
+            validate_deserialize(&opts, &fields, span)?;
+        }
+        if opts.is_unsafe.is_some() {
+            validate_unsafe(&opts, span)?;
+        }
+        if opts.unchecked.is_some() {
+            validate_unchecked(&opts, &fields, span)?;
+        }
 
-                opts
-            },
-            fields,
+        Ok(Self {
             struct_name,
+            vis,
             generics,
+            fields,
+            opts,
         })
     }
 }
 
-fn validate_opts(opts: &ContainerOptions, fields: &FieldsSource, span: Span) -> Result<(), Error> {
-    if opts.args.is_empty() && fields.fields().is_argless() {
+fn validate_fallible(
+    opts: &ContainerOptions,
+    fields: &FieldsSource,
+    span: Span,
+) -> Result<(), Error> {
+    let constructors = match *fields {
+        FieldsSource::Struct(ref fields) => vec![(opts.const_fn, fields)],
+        FieldsSource::Enum(ref variants) => variants
+            .iter()
+            .map(|v| (is_const_fn(opts, v), &v.fields))
+            .collect(),
+    };
+
+    if !constructors
+        .iter()
+        .any(move |&(_, fields)| opts.is_fallible(fields))
+    {
+        return if opts.error.is_some() || opts.panicking {
+            Err(Error::new(
+                span,
+                "The `error` and `panicking` options require a fallible constructor",
+            ))
+        } else {
+            Ok(())
+        };
+    }
+
+    // The generated error type can't be converted into from the validator's error, so there's
+    // always an explicit one
+    let has_checks = fields.has_checks();
+    if opts.validate.is_some() && opts.error.is_none() {
+        return Err(Error::new(
+            span,
+            "The `validate` option requires an `error` type",
+        ));
+    }
+
+    if opts.post.as_ref().is_some_and(PostHook::is_fallible) && opts.error.is_none() {
+        return Err(Error::new(
+            span,
+            "A `post(try_map)` hook requires an `error` type its errors convert into",
+        ));
+    }
+
+    for (const_fn, fields) in constructors {
+        if !opts.is_fallible(fields) {
+            continue;
+        }
+
+        if const_fn {
+            return Err(Error::new(
+                span,
+                "Fallible constructors cannot be `const_fn`",
+            ));
+        }
+
+        if opts.error.is_some() {
+            continue;
+        }
+
+        match fields.try_into_count() {
+            0 => {}
+            1 if !has_checks && !opts.checks_value() => {}
+            1 => {
+                return Err(Error::new(
+                    span,
+                    "Combining `try_into` fields with validators or `parse` fields requires an `error` type",
+                ));
+            }
+            _ => {
+                return Err(Error::new(
+                    span,
+                    "Multiple `try_into` fields require an `error` type all their errors convert into",
+                ));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn validate_async(opts: &ContainerOptions, fields: &FieldsSource, span: Span) -> Result<(), Error> {
+    if has_const_fn(opts, fields) {
+        Err(Error::new(
+            span,
+            "`async` constructors cannot be `const_fn`",
+        ))
+    } else if opts.default {
+        Err(Error::new(
+            span,
+            "The `default` option cannot be used with `async` constructors",
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+fn validate_returns(
+    opts: &ContainerOptions,
+    fields: &FieldsSource,
+    span: Span,
+) -> Result<(), Error> {
+    if has_const_fn(opts, fields) {
+        Err(Error::new(
+            span,
+            "The `returns` option cannot be combined with `const_fn`",
+        ))
+    } else if opts.default {
+        Err(Error::new(
+            span,
+            "The `default` option requires the constructor to return `Self` and cannot be combined with `returns`",
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+fn validate_weak_self(
+    opts: &ContainerOptions,
+    fields: &FieldsSource,
+    span: Span,
+) -> Result<(), Error> {
+    let weak_self_fields = match *fields {
+        FieldsSource::Struct(ref fields) => vec![fields],
+        FieldsSource::Enum(ref variants) => variants.iter().map(move |v| &v.fields).collect(),
+    }
+    .into_iter()
+    .filter(move |fields| fields.weak_self().is_some())
+    .collect::<Vec<_>>();
+
+    if weak_self_fields.is_empty() {
+        return Ok(());
+    }
+
+    let msg = if weak_self_fields.iter().any(move |fields| {
+        fields
+            .as_slice()
+            .iter()
+            .filter(|f| f.opts.weak_self)
+            .count()
+            > 1
+    }) {
+        "Only one field can be `weak_self`"
+    } else if weak_self_fields
+        .iter()
+        .any(move |fields| fields.try_into_count() != 0)
+    {
+        "`weak_self` cannot be combined with `try_into` fields"
+    } else if opts.validate.is_some() {
+        "`weak_self` cannot be combined with the `validate` option"
+    } else if opts.post.as_ref().is_some_and(PostHook::is_fallible) {
+        "`weak_self` cannot be combined with a `post(try_map)` hook"
+    } else if opts.is_async {
+        "`weak_self` cannot be combined with `async`"
+    } else if has_const_fn(opts, fields) {
+        "`weak_self` cannot be combined with `const_fn`"
+    } else if opts.default {
+        "The `default` option requires the constructor to return `Self` and cannot be combined with `weak_self`"
+    } else if opts.returns.as_ref().is_some_and(move |r| r.pinned) {
+        "`weak_self` cannot be combined with a pinned `returns` pointer"
+    } else if opts.returns.as_ref().is_some_and(move |r| !r.is_cyclic()) {
+        "`weak_self` requires the `returns` pointer to be an `Arc` or `Rc`"
+    } else {
+        return Ok(());
+    };
+
+    Err(Error::new(span, msg))
+}
+
+/// Whether any of the generated constructors is a `const fn`
+fn has_const_fn(opts: &ContainerOptions, fields: &FieldsSource) -> bool {
+    match *fields {
+        FieldsSource::Struct(_) => opts.const_fn,
+        FieldsSource::Enum(ref variants) => variants.iter().any(move |v| is_const_fn(opts, v)),
+    }
+}
+
+/// Whether the variant's constructor is a `const fn`
+fn is_const_fn(opts: &ContainerOptions, variant: &EnumVariant) -> bool {
+    variant.opts.const_fn.unwrap_or(opts.const_fn)
+}
+
+fn validate_builder(
+    opts: &ContainerOptions,
+    fields: &FieldsSource,
+    span: Span,
+) -> Result<(), Error> {
+    if opts.builder && opts.typestate_builder {
+        return Err(Error::new(
+            span,
+            "`builder` and `typestate_builder` are mutually exclusive",
+        ));
+    }
+
+    let FieldsSource::Struct(ref fields) = *fields else {
+        return Err(Error::new(span, "Builders are only supported on structs"));
+    };
+
+    // `builder` always reports unset arguments through the generated error type, which a
+    // `try_into` field's conversion error can't be converted into
+    if opts.builder && opts.error.is_none() && fields.try_into_count() != 0 {
+        return Err(Error::new(
+            span,
+            "Combining `builder` with `try_into` fields requires an `error` type",
+        ));
+    }
+
+    if let Some(arg) = opts
+        .args
+        .iter()
+        .find(move |arg| has_elided_lifetime(&arg.ty))
+    {
+        return Err(Error::new_spanned(
+            &arg.ty,
+            "Builders can't hold args with elided lifetimes - declare the lifetime on the struct",
+        ));
+    }
+
+    Ok(())
+}
+
+fn validate_args_struct(
+    opts: &ContainerOptions,
+    fields: &FieldsSource,
+    span: Span,
+) -> Result<(), Error> {
+    let FieldsSource::Struct(ref fields) = *fields else {
+        return Err(Error::new(
+            span,
+            "`args_struct` is only supported on structs",
+        ));
+    };
+
+    if opts.default {
+        return Err(Error::new(
+            span,
+            "The `default` option cannot be combined with `args_struct`",
+        ));
+    }
+
+    validate_held_args(opts, fields, "an args struct")
+}
+
+fn validate_deserialize(
+    opts: &ContainerOptions,
+    fields: &FieldsSource,
+    span: Span,
+) -> Result<(), Error> {
+    let msg = match *fields {
+        _ if !cfg!(feature = "serde") => "The `deserialize` option requires the `serde` feature",
+        FieldsSource::Enum(_) => "`deserialize` is only supported on structs",
+        _ if opts.is_async => "`deserialize` cannot be combined with `async`",
+        FieldsSource::Struct(ref fields)
+            if opts.returns.is_some() || fields.weak_self().is_some() =>
+        {
+            "`deserialize` requires the constructor to return `Self`"
+        }
+        FieldsSource::Struct(ref fields) => {
+            return validate_held_args(opts, fields, "a deserialized struct")
+        }
+    };
+
+    Err(Error::new(span, msg))
+}
+
+/// Args that get stored in `holder` need a concrete, nameable type
+fn validate_held_args(opts: &ContainerOptions, fields: &Fields, holder: &str) -> Result<(), Error> {
+    if let Some(arg) = opts
+        .args
+        .iter()
+        .find(move |arg| has_elided_lifetime(&arg.ty))
+    {
+        return Err(Error::new_spanned(
+            &arg.ty,
+            format!(
+                "Args with elided lifetimes can't be held by {holder} - declare the lifetime on the struct"
+            ),
+        ));
+    }
+
+    for field in fields.as_slice() {
+        if field.opts.should_skip_args() {
+            continue;
+        }
+
+        // There's no concrete type to hold these as
+        if let Some(conversion) = field
+            .opts
+            .conversions()
+            .into_iter()
+            .find(move |c| ["try_into", "boxed"].contains(c))
+        {
+            return Err(Error::new_spanned(
+                &field.ty,
+                format!("`{conversion}` arguments can't be held by {holder}"),
+            ));
+        }
+
+        if let Some(arg_ty) = &field.opts.arg_ty {
+            if matches!(*arg_ty, Type::ImplTrait(_)) || has_elided_lifetime(arg_ty) {
+                return Err(Error::new_spanned(
+                    arg_ty,
+                    format!("`impl Trait` or elided lifetime arguments can't be held by {holder}"),
+                ));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn validate_unsafe(opts: &ContainerOptions, span: Span) -> Result<(), Error> {
+    let msg = if opts
+        .is_unsafe
+        .as_ref()
+        .is_some_and(move |s| s.doc.is_none())
+    {
+        "`unsafe` constructors require a `# Safety` doc: `unsafe(\"...\")`"
+    } else if opts.default {
+        "The `default` option cannot be combined with `unsafe` constructors"
+    } else if opts.builder || opts.typestate_builder {
+        "Builders cannot be combined with `unsafe` constructors"
+    } else if opts.deserialize {
+        "`deserialize` cannot be combined with `unsafe` constructors"
+    } else {
+        return Ok(());
+    };
+
+    Err(Error::new(span, msg))
+}
+
+fn validate_unchecked(
+    opts: &ContainerOptions,
+    fields: &FieldsSource,
+    span: Span,
+) -> Result<(), Error> {
+    let fields = match *fields {
+        FieldsSource::Struct(ref fields) => vec![fields],
+        FieldsSource::Enum(ref variants) => variants.iter().map(move |v| &v.fields).collect(),
+    };
+
+    let msg = if opts.validate.is_none() && !fields.iter().any(move |f| f.has_validators()) {
+        "`unchecked` requires a `validate` option on the container or a field"
+    } else if fields
+        .iter()
+        .flat_map(move |f| f.as_slice())
+        .any(move |f| f.opts.parse || f.opts.try_into)
+    {
+        "`unchecked` cannot be combined with `parse` or `try_into` fields"
+    } else if opts.post.as_ref().is_some_and(PostHook::is_fallible) {
+        "`unchecked` cannot be combined with a `post(try_map)` hook"
+    } else {
+        return Ok(());
+    };
+
+    Err(Error::new(span, msg))
+}
+
+fn has_elided_lifetime(ty: &Type) -> bool {
+    match *ty {
+        Type::Reference(ref r) => {
+            r.lifetime.as_ref().map_or(true, move |l| l.ident == "_")
+                || has_elided_lifetime(&r.elem)
+        }
+        Type::Array(ref a) => has_elided_lifetime(&a.elem),
+        Type::Slice(ref s) => has_elided_lifetime(&s.elem),
+        Type::Paren(ref p) => has_elided_lifetime(&p.elem),
+        Type::Group(ref g) => has_elided_lifetime(&g.elem),
+        Type::Ptr(ref p) => has_elided_lifetime(&p.elem),
+        Type::Tuple(ref t) => t.elems.iter().any(has_elided_lifetime),
+        Type::Path(ref p) => p.path.segments.iter().any(move |seg| {
+            let PathArguments::AngleBracketed(ref args) = seg.arguments else {
+                return false;
+            };
+
+            args.args.iter().any(move |arg| match *arg {
+                GenericArgument::Lifetime(ref l) => l.ident == "_",
+                GenericArgument::Type(ref ty) => has_elided_lifetime(ty),
+                _ => false,
+            })
+        }),
+        _ => false,
+    }
+}
+
+fn validate_default(
+    opts: &ContainerOptions,
+    fields: &FieldsSource,
+    span: Span,
+) -> Result<(), Error> {
+    let (fields, variant_args) = match *fields {
+        FieldsSource::Struct(ref fields) => (fields, false),
+        FieldsSource::Enum(ref variants) => match variants.as_slice() {
+            [variant] => (&variant.fields, !variant.opts.args.is_empty()),
+            _ => {
+                return Err(Error::new(
+                    span,
+                    "The `default` option cannot be used when multiple constructors are generated",
+                ));
+            }
+        },
+    };
+
+    if opts.is_fallible(fields) && !opts.panicking {
+        return Err(Error::new(
+            span,
+            "The `default` option requires an infallible constructor - consider adding `panicking`",
+        ));
+    }
+
+    if opts.args.is_empty() && !variant_args && fields.is_argless() {
         Ok(())
     } else {
         Err(Error::new(
@@ -75,30 +505,54 @@ where
         .unwrap_or_else(Span::call_site)
 }
 
-fn find_variant<P>(span: &impl Spanned, variants: Punctuated<Variant, P>) -> syn::Result<Variant> {
-    let mut out = None;
+fn collect_variants<P>(
+    span: &impl Spanned,
+    variants: Punctuated<Variant, P>,
+    all_variants: bool,
+) -> syn::Result<Vec<EnumVariant>> {
+    let mut out = Vec::new();
     for variant in variants {
-        if variant
+        let mut marked = all_variants;
+        let attrs = variant
             .attrs
-            .iter()
-            .any(move |a| a.path().is_ident(ATTR_NAME))
-        {
-            if out.is_some() {
-                return Err(Error::new_spanned(
-                    variant.ident,
-                    "Multiple variants marked with `#[new]`",
-                ));
-            }
-            out = Some(variant);
+            .into_iter()
+            .filter(|a| {
+                if !a.path().is_ident(ATTR_NAME) {
+                    return false;
+                }
+
+                marked = true;
+
+                // A bare `#[new]` only marks the variant
+                !matches!(a.meta, Meta::Path(_))
+            })
+            .collect::<Vec<_>>();
+
+        if !marked {
+            continue;
         }
+
+        out.push(EnumVariant {
+            opts: if attrs.is_empty() {
+                VariantOptions::default()
+            } else {
+                VariantOptions::from_iter(create_span(&attrs), attrs)?
+            },
+            fields: variant.fields.try_into()?,
+            ident: variant.ident,
+        });
     }
 
-    if let Some(out) = out {
-        Ok(out)
-    } else {
+    if out.is_empty() {
         Err(Error::new(
             span.span(),
-            "Expected a variant marked with `#[new]`",
+            if all_variants {
+                "Expected at least one variant"
+            } else {
+                "Expected a variant marked with `#[new]`"
+            },
         ))
+    } else {
+        Ok(out)
     }
 }
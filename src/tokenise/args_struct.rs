@@ -0,0 +1,154 @@
+use proc_macro2::{Ident, TokenStream};
+use quote::{format_ident, quote, ToTokens};
+use syn::{Generics, Visibility};
+
+use super::{
+    make_arg_names, make_concrete_arg, make_where_clause, resolve_base_name,
+    resolve_return_wrapper, ConcreteArg, RESULT,
+};
+use crate::options::ContainerOptions;
+use crate::types::{ArgsStructOptions, Fields};
+
+/// A struct holding every constructor argument
+pub(super) struct ArgsStruct {
+    pub ident: Ident,
+
+    /// The struct's type, including the container's generics
+    pub ty: TokenStream,
+
+    /// Statements binding the struct's fields to the argument names, referencing the ones the
+    /// constructor takes by reference
+    pub unpack: TokenStream,
+
+    /// The struct & its conversion impl
+    pub items: TokenStream,
+}
+
+pub(super) fn make_args_struct(
+    struct_name: &Ident,
+    vis: &Visibility,
+    generics: &Generics,
+    opts: &ContainerOptions,
+    args_opts: &ArgsStructOptions,
+    fields: &Fields,
+    error_ident: Option<&Ident>,
+) -> ArgsStruct {
+    let ident = if let Some(name) = &args_opts.name {
+        name.clone()
+    } else {
+        format_ident!("{}Args", struct_name)
+    };
+    let vis = args_opts.vis.as_ref().unwrap_or(vis);
+    let (_, ty_generics, where_clause) = generics.split_for_impl();
+
+    let custom_args = opts
+        .args
+        .iter()
+        .map(move |arg| (&arg.ident, arg.ty.to_token_stream()));
+    let field_args = fields
+        .as_slice()
+        .iter()
+        .enumerate()
+        .filter(move |(_, field)| !field.opts.should_skip_args())
+        .map(move |(idx, field)| (field.resolve_ident(), make_concrete_arg(field, idx)))
+        .collect::<Vec<_>>();
+    let rebind = field_args
+        .iter()
+        .filter(move |(_, arg)| arg.by_ref)
+        .map(move |(ident, _)| quote!(let #ident = &#ident;));
+    let arg_names = make_arg_names(fields, &opts.args);
+    let unpack = quote! {
+        let #ident { #arg_names } = args;
+        #(#rebind)*
+    };
+    let field_args = field_args
+        .into_iter()
+        .map(move |(ident, ConcreteArg { ty, .. })| (ident, ty));
+    let struct_fields = custom_args.chain(field_args).map(move |(ident, ty)| {
+        let doc = format!("The `{ident}` argument.");
+        quote! {
+            #[doc = #doc]
+            #vis #ident: #ty
+        }
+    });
+
+    let derive = if args_opts.derive.is_empty() {
+        TokenStream::new()
+    } else {
+        let derive = &args_opts.derive;
+        quote!(#[derive(#derive)])
+    };
+    let doc = format!("Arguments for constructing a [`{struct_name}`].");
+    let conversion = make_conversion(struct_name, generics, opts, fields, &ident, error_ident);
+
+    let items = quote! {
+        #[doc = #doc]
+        #derive
+        #vis struct #ident #generics #where_clause {
+            #(#struct_fields,)*
+        }
+
+        #conversion
+    };
+
+    ArgsStruct {
+        ty: quote!(#ident #ty_generics),
+        ident,
+        unpack,
+        items,
+    }
+}
+
+/// `From` for infallible constructors, `TryFrom` for fallible ones & nothing for constructors
+/// that are `unsafe` or don't return `Self` synchronously
+fn make_conversion(
+    struct_name: &Ident,
+    generics: &Generics,
+    opts: &ContainerOptions,
+    fields: &Fields,
+    ident: &Ident,
+    error_ident: Option<&Ident>,
+) -> TokenStream {
+    if opts.is_async || opts.is_unsafe.is_some() || resolve_return_wrapper(opts, fields).is_some() {
+        return TokenStream::new();
+    }
+
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let predicates = where_clause
+        .into_iter()
+        .flat_map(move |w| w.predicates.iter().map(ToTokens::to_token_stream))
+        .chain(opts.bounds.iter().map(ToTokens::to_token_stream));
+    let where_clause = make_where_clause(predicates);
+    let name = resolve_base_name(opts);
+
+    if !opts.is_fallible(fields) {
+        return quote! {
+            #[automatically_derived]
+            impl #impl_generics ::core::convert::From<#ident #ty_generics> for #struct_name #ty_generics #where_clause {
+                #[inline]
+                fn from(args: #ident #ty_generics) -> Self {
+                    Self::#name(args)
+                }
+            }
+        };
+    }
+
+    let error = if let Some(error) = &opts.error {
+        error.to_token_stream()
+    } else {
+        error_ident.to_token_stream()
+    };
+    let try_name = format_ident!("try_{}", name);
+
+    quote! {
+        #[automatically_derived]
+        impl #impl_generics ::core::convert::TryFrom<#ident #ty_generics> for #struct_name #ty_generics #where_clause {
+            type Error = #error;
+
+            #[inline]
+            fn try_from(args: #ident #ty_generics) -> #RESULT<Self, Self::Error> {
+                Self::#try_name(args)
+            }
+        }
+    }
+}
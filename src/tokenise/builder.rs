@@ -0,0 +1,312 @@
+use macroific::elements::GenericImpl;
+use proc_macro2::{Ident, TokenStream};
+use quote::{format_ident, quote, ToTokens};
+use syn::{Generics, Visibility};
+
+use super::{
+    make_arg_type, make_asyncness, make_await, make_conversion, make_fallible_body,
+    make_return_type, make_where_clause, override_ident, try_into_param, wrap_return_type,
+    ArgsMode, PHANTOM_DATA, RESULT, TRAIT_DEFAULT,
+};
+use crate::options::ContainerOptions;
+use crate::types::Fields;
+
+/// A value held by the builder
+pub(super) struct Slot<'a> {
+    /// The builder's field name, which is also the setter's name
+    pub ident: &'a Ident,
+
+    /// The type stored in the builder
+    pub ty: TokenStream,
+
+    /// The setter's signature & the expression storing its argument
+    pub setter: Setter,
+
+    /// `None` if the value is a required argument, the local variable name holding the override
+    /// otherwise
+    pub override_ident: Option<Ident>,
+}
+
+pub(super) struct Setter {
+    pub doc: String,
+    pub generics: TokenStream,
+    pub arg_ty: TokenStream,
+    pub store: TokenStream,
+    pub fallible_error: Option<TokenStream>,
+}
+
+pub(super) struct Builder {
+    /// Items to add to the struct's own `impl` block
+    pub inherent: TokenStream,
+
+    /// Items to add after the struct's `impl` block
+    pub items: TokenStream,
+}
+
+pub(super) fn make_builder(
+    struct_name: &Ident,
+    vis: &Visibility,
+    generics: &Generics,
+    opts: &ContainerOptions,
+    fields: &Fields,
+    error_ident: &Ident,
+) -> Builder {
+    let builder_ident = format_ident!("{}Builder", struct_name);
+    let (_, ty_generics, where_clause) = generics.split_for_impl();
+    let fn_vis = if let Some(vis) = &opts.vis {
+        vis.to_token_stream()
+    } else {
+        quote!(pub)
+    };
+    let fn_where = make_where_clause(opts.bounds.iter().map(ToTokens::to_token_stream));
+
+    let slots = collect_slots(opts, fields);
+    let (asyncness, await_) = (make_asyncness(opts), make_await(opts));
+    let self_ret = make_return_type(opts, fields);
+    let ret = wrap_return_type(opts, fields, quote!(#struct_name #ty_generics));
+
+    let struct_fields = slots.iter().map(move |slot| {
+        let Slot { ident, ty, .. } = slot;
+        quote!(#ident: ::core::option::Option<#ty>)
+    });
+    let nones = slots.iter().map(move |slot| {
+        let ident = slot.ident;
+        quote!(#ident: ::core::option::Option::None)
+    });
+
+    let setters = slots
+        .iter()
+        .map(|slot| make_setter(slot, &fn_vis, &fn_where));
+
+    let error = if let Some(error) = &opts.error {
+        error.to_token_stream()
+    } else {
+        error_ident.to_token_stream()
+    };
+
+    let inherent = {
+        let destructure = slots.iter().map(move |slot| {
+            let ident = slot.ident;
+            if let Some(ref override_ident) = slot.override_ident {
+                quote!(#ident: #override_ident)
+            } else {
+                ident.to_token_stream()
+            }
+        });
+
+        let unwrap_required = make_unwrap_required(&slots, error_ident);
+
+        let body = make_fallible_body(opts, None, fields, Some(error_ident), ArgsMode::Builder);
+        let builder_doc = format!("Creates a builder for [`{struct_name}`].");
+
+        quote! {
+            #[doc = #builder_doc]
+            #[inline]
+            #fn_vis fn builder() -> #builder_ident #ty_generics #fn_where {
+                #TRAIT_DEFAULT::default()
+            }
+
+            #[doc(hidden)]
+            #asyncness fn __build(builder: #builder_ident #ty_generics) -> #RESULT<#self_ret, #error> #fn_where {
+                let #builder_ident { #(#destructure,)* __marker: _ } = builder;
+                #unwrap_required
+                #body
+            }
+        }
+    };
+
+    let builder_doc = format!("Builder for [`{struct_name}`].");
+    let build_doc = format!("Constructs the [`{struct_name}`].");
+    let impl_header = GenericImpl::new(generics).with_target(&builder_ident);
+    let default_header = GenericImpl::new(generics)
+        .with_trait(TRAIT_DEFAULT)
+        .with_target(&builder_ident);
+
+    let items = quote! {
+        #[doc = #builder_doc]
+        #vis struct #builder_ident #generics #where_clause {
+            #(#struct_fields,)*
+            __marker: #PHANTOM_DATA<fn() -> #struct_name #ty_generics>,
+        }
+
+        #[automatically_derived]
+        #[allow(clippy::all)]
+        #impl_header {
+            #(#setters)*
+
+            #[doc = #build_doc]
+            #[doc = ""]
+            #[doc = " # Errors"]
+            #[doc = ""]
+            #[doc = " If a required argument hasn't been set or if the constructor fails."]
+            #fn_vis #asyncness fn build(self) -> #RESULT<#ret, #error> #fn_where {
+                #struct_name::__build(self) #await_
+            }
+        }
+
+        #[automatically_derived]
+        #default_header {
+            #[inline]
+            fn default() -> Self {
+                Self {
+                    #(#nones,)*
+                    __marker: #PHANTOM_DATA,
+                }
+            }
+        }
+    };
+
+    Builder { inherent, items }
+}
+
+/// Check that every required argument has been set, collecting the ones that haven't
+fn make_unwrap_required(slots: &[Slot], error_ident: &Ident) -> TokenStream {
+    let required = slots
+        .iter()
+        .filter(move |slot| slot.override_ident.is_none())
+        .map(move |slot| slot.ident)
+        .collect::<Vec<_>>();
+
+    if required.is_empty() {
+        TokenStream::new()
+    } else {
+        let names = required.iter().map(ToString::to_string);
+
+        quote! {
+            let mut __errors = ::std::vec::Vec::new();
+            #(
+                if #required.is_none() {
+                    __errors.push((#names, ::std::string::String::from("not set")));
+                }
+            )*
+            let (#(::core::option::Option::Some(#required),)*) = (#(#required,)*) else {
+                return #RESULT::Err(::core::convert::From::from(#error_ident { errors: __errors }));
+            };
+        }
+    }
+}
+
+pub(super) fn make_setter(
+    slot: &Slot,
+    fn_vis: &TokenStream,
+    fn_where: &TokenStream,
+) -> TokenStream {
+    let ident = slot.ident;
+    let Setter {
+        doc,
+        generics,
+        arg_ty,
+        store,
+        fallible_error,
+    } = &slot.setter;
+
+    if let Some(error) = fallible_error {
+        quote! {
+            #[doc = #doc]
+            #[doc = ""]
+            #[doc = " # Errors"]
+            #[doc = ""]
+            #[doc = " If the conversion fails."]
+            #fn_vis fn #ident #generics(mut self, #ident: #arg_ty) -> #RESULT<Self, #error> #fn_where {
+                self.#ident = ::core::option::Option::Some(#store);
+                #RESULT::Ok(self)
+            }
+        }
+    } else {
+        quote! {
+            #[doc = #doc]
+            #[must_use]
+            #fn_vis fn #ident #generics(mut self, #ident: #arg_ty) -> Self #fn_where {
+                self.#ident = ::core::option::Option::Some(#store);
+                self
+            }
+        }
+    }
+}
+
+pub(super) fn collect_slots<'a>(opts: &'a ContainerOptions, fields: &'a Fields) -> Vec<Slot<'a>> {
+    let args = opts.args.iter().map(move |arg| {
+        let ty = &arg.ty;
+        Slot {
+            ident: &arg.ident,
+            ty: ty.to_token_stream(),
+            setter: Setter {
+                doc: format!("Sets the `{}` argument.", arg.ident),
+                generics: TokenStream::new(),
+                arg_ty: ty.to_token_stream(),
+                store: arg.ident.to_token_stream(),
+                fallible_error: None,
+            },
+            override_ident: None,
+        }
+    });
+
+    let fields = fields
+        .as_slice()
+        .iter()
+        .enumerate()
+        .filter(move |(_, field)| !field.opts.should_skip_args() || field.is_overridable())
+        .map(move |(idx, field)| {
+        let ident = field.resolve_ident();
+        let ty = &field.ty;
+
+        if field.opts.should_skip_args() {
+            return Slot {
+                ident,
+                ty: ty.to_token_stream(),
+                setter: Setter {
+                    doc: format!("Overrides the `{ident}` field's computed value."),
+                    generics: TokenStream::new(),
+                    arg_ty: ty.to_token_stream(),
+                    store: ident.to_token_stream(),
+                    fallible_error: None,
+                },
+                override_ident: Some(override_ident(field)),
+            };
+        }
+
+        let doc = format!("Sets the `{ident}` argument.");
+        let arg_ty = make_arg_type(field, idx);
+
+        let setter = if field.opts.try_into {
+            let param = try_into_param(idx);
+            Setter {
+                doc,
+                generics: quote!(<#param: ::core::convert::TryInto<#ty>>),
+                arg_ty,
+                store: quote!(::core::convert::TryInto::try_into(#ident)?),
+                fallible_error: Some(quote!(<#param as ::core::convert::TryInto<#ty>>::Error)),
+            }
+        } else if field.opts.parse {
+            // Parsing happens on build so the errors get collected like they do in the constructor
+            Setter {
+                doc,
+                generics: TokenStream::new(),
+                arg_ty,
+                store: quote!(::std::string::String::from(::core::convert::AsRef::<str>::as_ref(&#ident))),
+                fallible_error: None,
+            }
+        } else {
+            Setter {
+                doc,
+                generics: TokenStream::new(),
+                arg_ty,
+                store: make_conversion(field),
+                fallible_error: None,
+            }
+        };
+
+        Slot {
+            ident,
+            ty: if field.opts.parse {
+                quote!(::std::string::String)
+            } else {
+                ty.to_token_stream()
+            },
+            setter,
+            override_ident: None,
+        }
+    });
+
+    args.chain(fields).collect()
+}
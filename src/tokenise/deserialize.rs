@@ -0,0 +1,114 @@
+use proc_macro2::{Ident, TokenStream};
+use quote::{format_ident, quote, ToTokens};
+use syn::{Generics, WherePredicate};
+
+use super::args_struct::ArgsStruct;
+use super::{make_concrete_arg, resolve_base_name, ConcreteArg, RESULT};
+use crate::options::ContainerOptions;
+use crate::types::{Field, Fields};
+
+/// A constructor argument as it gets deserialized
+struct Arg<'a> {
+    ident: &'a Ident,
+
+    /// The type it gets deserialized as
+    ty: TokenStream,
+
+    /// The expression passing it to the constructor
+    pass: TokenStream,
+}
+
+/// Generates a hidden struct deserializing every constructor argument & a `Deserialize`
+/// implementation passing them to the constructor.
+pub(super) fn make_deserialize(
+    struct_name: &Ident,
+    generics: &Generics,
+    opts: &ContainerOptions,
+    fields: &Fields,
+    args_struct: Option<&ArgsStruct>,
+) -> TokenStream {
+    let args_ident = format_ident!("__{}Args", struct_name);
+    let (_, ty_generics, where_clause) = generics.split_for_impl();
+
+    let custom_args = opts.args.iter().map(move |arg| Arg {
+        ident: &arg.ident,
+        ty: arg.ty.to_token_stream(),
+        pass: arg.ident.to_token_stream(),
+    });
+    let field_args = fields
+        .as_slice()
+        .iter()
+        .enumerate()
+        .filter(move |(_, field)| !field.opts.should_skip_args())
+        .map(move |(idx, field)| make_arg(field, idx, args_struct.is_some()));
+    let args = custom_args.chain(field_args).collect::<Vec<_>>();
+
+    let struct_fields = args
+        .iter()
+        .map(move |Arg { ident, ty, .. }| quote!(#ident: #ty));
+    let idents = args.iter().map(move |arg| arg.ident).collect::<Vec<_>>();
+    let passes = args.iter().map(move |arg| &arg.pass);
+    let call_args = if let Some(args_struct) = args_struct {
+        let ident = &args_struct.ident;
+        quote!(#ident { #(#idents: #passes),* })
+    } else {
+        quote!(#(#passes),*)
+    };
+
+    let name = resolve_base_name(opts);
+    let construct = if opts.is_fallible(fields) {
+        let try_name = format_ident!("try_{}", name);
+        quote! {
+            #RESULT::map_err(Self::#try_name(#call_args), ::serde::de::Error::custom)
+        }
+    } else {
+        quote!(#RESULT::Ok(Self::#name(#call_args)))
+    };
+
+    let mut impl_generics = generics.clone();
+    impl_generics.params.insert(0, syn::parse_quote!('__de));
+    {
+        let where_clause = impl_generics.make_where_clause();
+        let args_bound: WherePredicate =
+            syn::parse_quote!(#args_ident #ty_generics: ::serde::Deserialize<'__de>);
+        where_clause.predicates.push(args_bound);
+        where_clause.predicates.extend(
+            opts.bounds
+                .iter()
+                .map(move |bound| -> WherePredicate { syn::parse_quote!(#bound) }),
+        );
+    }
+    let (impl_generics, _, impl_where) = impl_generics.split_for_impl();
+    let rename = struct_name.to_string();
+
+    quote! {
+        const _: () = {
+            #[derive(::serde::Deserialize)]
+            #[serde(rename = #rename)]
+            struct #args_ident #generics #where_clause {
+                #(#struct_fields,)*
+            }
+
+            #[automatically_derived]
+            impl #impl_generics ::serde::Deserialize<'__de> for #struct_name #ty_generics #impl_where {
+                fn deserialize<__D: ::serde::Deserializer<'__de>>(deserializer: __D) -> #RESULT<Self, __D::Error> {
+                    let #args_ident { #(#idents),* } = <#args_ident #ty_generics as ::serde::Deserialize<'__de>>::deserialize(deserializer)?;
+                    #construct
+                }
+            }
+        };
+    }
+}
+
+/// Args structs hold the same concrete types, so their fields get passed as-is
+fn make_arg(field: &Field, idx: usize, to_args_struct: bool) -> Arg<'_> {
+    let ident = field.resolve_ident();
+    let ConcreteArg { ty, by_ref } = make_concrete_arg(field, idx);
+    let pass = if by_ref && !to_args_struct {
+        quote!(&#ident)
+    } else {
+        ident.to_token_stream()
+    };
+
+    Arg { ident, ty, pass }
+}
@@ -0,0 +1,1146 @@
+use macroific::elements::{GenericImpl, ModulePrefix};
+use macroific::prelude::*;
+use proc_macro2::{Delimiter, Group, Ident, Punct, Span, TokenStream};
+use quote::{format_ident, quote, ToTokens, TokenStreamExt};
+use syn::punctuated::Punctuated;
+use syn::{Expr, Generics, LitStr, Pat, PatType, Token, Type, TypeParamBound, Visibility};
+
+use crate::options::{ArgReference, ContainerOptions};
+use crate::types::{
+    collection_item, dyn_pointer, map_entry, option_inner, referenced_idents, unwrapped_ty,
+    EnumVariant, Field, Fields, FieldsSource, MiniField, ReturnWrapper, SafetyDoc,
+};
+use crate::FancyConstructor;
+use args_struct::ArgsStruct;
+
+mod args_struct;
+mod builder;
+mod deserialize;
+mod typestate;
+
+const TRAIT_DEFAULT: ModulePrefix<3> = ModulePrefix::new(["core", "default", "Default"]);
+const RESULT: ModulePrefix<3> = ModulePrefix::new(["core", "result", "Result"]);
+const PHANTOM_DATA: ModulePrefix<3> = ModulePrefix::new(["core", "marker", "PhantomData"]);
+const NAME_DEFAULT: &str = "new";
+
+impl FancyConstructor {
+    #[inline]
+    pub fn into_token_stream(self) -> TokenStream {
+        let Self {
+            struct_name,
+            vis,
+            generics,
+            fields,
+            opts,
+        } = self;
+
+        let header = GenericImpl::new(&generics).with_target(&struct_name);
+        let default = make_default(&generics, &struct_name, &opts, &fields);
+
+        let error_ident = if fields.has_checks() || opts.builder {
+            Some(format_ident!("{}ConstructError", struct_name))
+        } else {
+            None
+        };
+        let error_type = match error_ident {
+            Some(ref ident) => make_error_type(&vis, &struct_name, ident),
+            None => TokenStream::new(),
+        };
+
+        let builder = match (&fields, &error_ident) {
+            (FieldsSource::Struct(fields), Some(error_ident)) if opts.builder => Some(
+                builder::make_builder(&struct_name, &vis, &generics, &opts, fields, error_ident),
+            ),
+            (FieldsSource::Struct(fields), error_ident) if opts.typestate_builder => {
+                Some(typestate::make_typestate_builder(
+                    &struct_name,
+                    &vis,
+                    &generics,
+                    &opts,
+                    fields,
+                    error_ident.as_ref(),
+                ))
+            }
+            _ => None,
+        };
+        let (builder_inherent, builder_items) = match builder {
+            Some(builder::Builder { inherent, items }) => (inherent, items),
+            None => (TokenStream::new(), TokenStream::new()),
+        };
+
+        let args_struct = match (&fields, &opts.args_struct) {
+            (FieldsSource::Struct(fields), Some(args_opts)) => Some(args_struct::make_args_struct(
+                &struct_name,
+                &vis,
+                &generics,
+                &opts,
+                args_opts,
+                fields,
+                error_ident.as_ref(),
+            )),
+            _ => None,
+        };
+        let args_struct_items = args_struct.as_ref().map(move |a| &a.items);
+        let deserialize = match fields {
+            FieldsSource::Struct(ref fields) if opts.deserialize => deserialize::make_deserialize(
+                &struct_name,
+                &generics,
+                &opts,
+                fields,
+                args_struct.as_ref(),
+            ),
+            _ => TokenStream::new(),
+        };
+
+        let body = make_container_body(opts, fields, error_ident.as_ref(), args_struct.as_ref());
+
+        quote! {
+            #[automatically_derived]
+            #[allow(clippy::all)]
+            #header {
+                #body
+                #builder_inherent
+            }
+
+            #default
+            #error_type
+            #builder_items
+            #args_struct_items
+            #deserialize
+        }
+    }
+}
+
+#[inline]
+fn make_default(
+    generics: &Generics,
+    struct_name: &Ident,
+    opts: &ContainerOptions,
+    fields: &FieldsSource,
+) -> TokenStream {
+    if !opts.default {
+        return TokenStream::new();
+    }
+
+    let header = GenericImpl::new(&generics)
+        .with_trait(TRAIT_DEFAULT)
+        .with_target(&struct_name);
+
+    let new_name = match *fields {
+        FieldsSource::Enum(ref variants) if variants.len() == 1 => {
+            resolve_name(opts, &variants[0], false)
+        }
+        _ => resolve_base_name(opts),
+    };
+
+    quote! {
+        #[automatically_derived]
+        #[allow(clippy::all)]
+        #header {
+            #[inline]
+            fn default() -> Self {
+                #struct_name::#new_name()
+            }
+        }
+    }
+}
+
+fn resolve_base_name(opts: &ContainerOptions) -> Ident {
+    if let Some(name) = &opts.name {
+        name.clone()
+    } else {
+        Ident::create(NAME_DEFAULT)
+    }
+}
+
+/// Resolve a variant's constructor name. `multi` indicates whether multiple constructors are being
+/// generated, in which case the default name gets suffixed with the variant's name.
+fn resolve_name(opts: &ContainerOptions, variant: &EnumVariant, multi: bool) -> Ident {
+    if let Some(name) = &variant.opts.name {
+        name.clone()
+    } else if multi {
+        format_ident!(
+            "{}_{}",
+            resolve_base_name(opts),
+            to_snake_case(&variant.ident)
+        )
+    } else {
+        resolve_base_name(opts)
+    }
+}
+
+fn to_snake_case(ident: &Ident) -> String {
+    let ident = ident.to_string();
+    let chars = ident.chars().collect::<Vec<_>>();
+    let mut out = String::with_capacity(ident.len() + 4);
+
+    for (idx, &ch) in chars.iter().enumerate() {
+        if ch.is_uppercase() {
+            if idx != 0 {
+                let prev = chars[idx - 1];
+                let next_lower = chars.get(idx + 1).is_some_and(|c| c.is_lowercase());
+                if prev != '_' && (!prev.is_uppercase() || next_lower) {
+                    out.push('_');
+                }
+            }
+            out.extend(ch.to_lowercase());
+        } else {
+            out.push(ch);
+        }
+    }
+
+    out
+}
+
+fn make_error_type(vis: &Visibility, struct_name: &Ident, error_ident: &Ident) -> TokenStream {
+    let doc = format!("Argument errors produced when constructing a [`{struct_name}`].");
+
+    quote! {
+        #[doc = #doc]
+        #[derive(Debug, Clone, PartialEq, Eq)]
+        #vis struct #error_ident {
+            /// Argument name & error message pairs for every argument that failed parsing or validation.
+            pub errors: ::std::vec::Vec<(&'static str, ::std::string::String)>,
+        }
+
+        #[automatically_derived]
+        impl ::core::fmt::Display for #error_ident {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                for (idx, (arg, message)) in self.errors.iter().enumerate() {
+                    if idx != 0 {
+                        f.write_str("; ")?;
+                    }
+                    ::core::write!(f, "invalid `{}`: {}", arg, message)?;
+                }
+
+                #RESULT::Ok(())
+            }
+        }
+
+        #[automatically_derived]
+        impl ::std::error::Error for #error_ident {}
+    }
+}
+
+#[inline]
+fn make_container_body(
+    opts: ContainerOptions,
+    fields: FieldsSource,
+    error_ident: Option<&Ident>,
+    args_struct: Option<&ArgsStruct>,
+) -> TokenStream {
+    match fields {
+        FieldsSource::Struct(fields) => make_constructor(
+            &opts,
+            resolve_base_name(&opts),
+            None,
+            quote!(#[doc = "Constructs a new instance of the struct."]),
+            fields,
+            error_ident,
+            args_struct,
+        ),
+        FieldsSource::Enum(variants) => {
+            let multi = variants.len() > 1;
+
+            variants
+                .into_iter()
+                .map(move |variant| {
+                    let name = resolve_name(&opts, &variant, multi);
+                    let doc = format!("Constructs a new `{}` variant.", variant.ident);
+                    let opts = opts.with_variant(variant.opts);
+
+                    make_constructor(
+                        &opts,
+                        name,
+                        Some(variant.ident),
+                        quote!(#[doc = #doc]),
+                        variant.fields,
+                        error_ident,
+                        None,
+                    )
+                })
+                .collect()
+        }
+    }
+}
+
+/// How the constructor arguments are bound in the fn body
+#[derive(Copy, Clone, Eq, PartialEq)]
+enum ArgsMode {
+    /// As they're declared by [`make_args`]
+    Raw,
+
+    /// Converted to their fields' types, with overrides for fields that are skipped from the args
+    Builder,
+}
+
+/// Signature components shared by every fn generated for a constructor
+struct Signature {
+    doc: TokenStream,
+    vis: TokenStream,
+    const_fn: TokenStream,
+    asyncness: TokenStream,
+    unsafety: TokenStream,
+    args: TokenStream,
+
+    /// The arguments as they're passed on to another constructor
+    arg_names: TokenStream,
+
+    /// Statements binding the arguments to their names if they're taken via an args struct
+    unpack: TokenStream,
+    predicates: Vec<TokenStream>,
+}
+
+fn make_constructor(
+    opts: &ContainerOptions,
+    name: Ident,
+    variant: Option<Ident>,
+    default_doc: TokenStream,
+    fields: Fields,
+    error_ident: Option<&Ident>,
+    args_struct: Option<&ArgsStruct>,
+) -> TokenStream {
+    // Only constructors that have validators to skip get an unchecked counterpart
+    let unchecked =
+        if opts.unchecked.is_some() && (opts.validate.is_some() || fields.has_validators()) {
+            make_unchecked_constructor(
+                opts,
+                &name,
+                variant.clone(),
+                &default_doc,
+                &fields,
+                args_struct,
+            )
+        } else {
+            TokenStream::new()
+        };
+    let (args, arg_names, unpack) = if let Some(args_struct) = args_struct {
+        let ty = &args_struct.ty;
+        (quote!(args: #ty), quote!(args), args_struct.unpack.clone())
+    } else {
+        (
+            make_args(&fields, &opts.args),
+            make_arg_names(&fields, &opts.args),
+            TokenStream::new(),
+        )
+    };
+
+    let mut doc = if let Some(comment) = &opts.comment {
+        quote!(#[doc = #comment])
+    } else {
+        default_doc
+    };
+    if let Some(safety) = opts.is_unsafe.as_ref().and_then(move |s| s.doc.as_ref()) {
+        let safety = format!(" {}", safety.value());
+        doc.extend(quote! {
+            #[doc = ""]
+            #[doc = " # Safety"]
+            #[doc = ""]
+            #[doc = #safety]
+        });
+    }
+
+    let sig = Signature {
+        doc,
+        vis: if let Some(vis) = &opts.vis {
+            vis.to_token_stream()
+        } else {
+            quote!(pub)
+        },
+        const_fn: if opts.const_fn {
+            quote!(const)
+        } else {
+            TokenStream::new()
+        },
+        asyncness: make_asyncness(opts),
+        unsafety: if opts.is_unsafe.is_some() {
+            quote!(unsafe)
+        } else {
+            TokenStream::new()
+        },
+        args,
+        arg_names,
+        unpack,
+        predicates: opts.bounds.iter().map(ToTokens::to_token_stream).collect(),
+    };
+
+    if opts.is_fallible(&fields) {
+        let mut tokens = make_fallible_constructor(sig, opts, name, variant, fields, error_ident);
+        tokens.extend(unchecked);
+        return tokens;
+    }
+
+    let Signature {
+        doc,
+        vis,
+        const_fn,
+        asyncness,
+        unsafety,
+        args,
+        arg_names: _,
+        unpack,
+        predicates,
+    } = sig;
+    let where_clause = make_where_clause(predicates);
+    let body = wrap_return(
+        opts,
+        &fields,
+        apply_post(
+            opts,
+            make_fn_body(opts, variant.as_ref(), &fields, ArgsMode::Raw),
+        ),
+    );
+    let ret = make_return_type(opts, &fields);
+
+    quote! {
+        #doc
+        #vis #const_fn #asyncness #unsafety fn #name(#args) -> #ret #where_clause {
+            #unpack
+            #body
+        }
+
+        #unchecked
+    }
+}
+
+/// Make an unsafe `{name}_unchecked` constructor that skips the container's & fields' validators
+fn make_unchecked_constructor(
+    opts: &ContainerOptions,
+    name: &Ident,
+    variant: Option<Ident>,
+    default_doc: &TokenStream,
+    fields: &Fields,
+    args_struct: Option<&ArgsStruct>,
+) -> TokenStream {
+    let mut safety = opts
+        .is_unsafe
+        .iter()
+        .chain(&opts.unchecked)
+        .filter_map(move |s| s.doc.as_ref().map(LitStr::value))
+        .collect::<Vec<_>>();
+    if opts
+        .unchecked
+        .as_ref()
+        .is_some_and(move |s| s.doc.is_none())
+    {
+        safety.push(String::from(
+            "The arguments & the constructed value must pass the validation this constructor skips.",
+        ));
+    }
+
+    let doc = if let Some(comment) = &opts.comment {
+        quote!(#[doc = #comment])
+    } else {
+        default_doc.clone()
+    };
+    let opts = ContainerOptions {
+        comment: None,
+        validate: None,
+        panicking: false,
+        is_unsafe: Some(SafetyDoc {
+            doc: Some(LitStr::new(&safety.join("\n\n"), Span::call_site())),
+        }),
+        unchecked: None,
+        ..opts.clone()
+    };
+
+    make_constructor(
+        &opts,
+        format_ident!("{}_unchecked", name),
+        variant,
+        quote! {
+            #doc
+            #[doc = ""]
+            #[doc = " Skips validating the arguments & the constructed value."]
+        },
+        fields.without_validators(),
+        None,
+        args_struct,
+    )
+}
+
+/// Make a `try_{name}` constructor returning a `Result` & a panicking `{name}` one if requested
+fn make_fallible_constructor(
+    sig: Signature,
+    opts: &ContainerOptions,
+    name: Ident,
+    variant: Option<Ident>,
+    fields: Fields,
+    error_ident: Option<&Ident>,
+) -> TokenStream {
+    let Signature {
+        doc,
+        vis,
+        const_fn: _,
+        asyncness,
+        unsafety,
+        args,
+        arg_names,
+        unpack,
+        mut predicates,
+    } = sig;
+
+    let try_into_fields = fields
+        .as_slice()
+        .iter()
+        .enumerate()
+        .filter(move |(_, field)| field.opts.try_into)
+        .map(move |(idx, field)| (try_into_param(idx), &field.ty))
+        .collect::<Vec<_>>();
+
+    let generics = if try_into_fields.is_empty() {
+        TokenStream::new()
+    } else {
+        let params = try_into_fields
+            .iter()
+            .map(move |(param, ty)| quote!(#param: ::core::convert::TryInto<#ty>));
+
+        quote!(<#(#params),*>)
+    };
+
+    let mut panicking_predicates = Vec::new();
+    let error = match (&opts.error, error_ident) {
+        (Some(error), _) => {
+            predicates.extend(try_into_fields.iter().map(move |(param, ty)| {
+                quote! {
+                    #error: ::core::convert::From<<#param as ::core::convert::TryInto<#ty>>::Error>
+                }
+            }));
+
+            error.to_token_stream()
+        }
+        (None, Some(ident)) => ident.to_token_stream(),
+        (None, None) => {
+            // Sole `try_into` field: return its error as-is
+            let Some((param, ty)) = try_into_fields.first() else {
+                unreachable!("Error type should've been validated at parse time");
+            };
+            let error = quote!(<#param as ::core::convert::TryInto<#ty>>::Error);
+            panicking_predicates.push(quote!(#error: ::core::fmt::Display));
+
+            error
+        }
+    };
+
+    let try_name = format_ident!("try_{}", name);
+    let ret = make_return_type(opts, &fields);
+    let panicking = if opts.panicking {
+        let where_clause =
+            make_where_clause(predicates.iter().cloned().chain(panicking_predicates));
+        let await_ = make_await(opts);
+        let call = if opts.is_unsafe.is_some() {
+            quote!(unsafe { Self::#try_name(#arg_names) })
+        } else {
+            quote!(Self::#try_name(#arg_names))
+        };
+
+        quote! {
+            #doc
+            #[doc = ""]
+            #[doc = " # Panics"]
+            #[doc = ""]
+            #[doc = " If the constructor fails."]
+            #vis #asyncness #unsafety fn #name #generics(#args) -> #ret #where_clause {
+                match #call #await_ {
+                    #RESULT::Ok(v) => v,
+                    #RESULT::Err(e) => ::core::panic!("{}", e),
+                }
+            }
+        }
+    } else {
+        TokenStream::new()
+    };
+
+    let body = make_fallible_body(opts, variant.as_ref(), &fields, error_ident, ArgsMode::Raw);
+    let where_clause = make_where_clause(predicates);
+
+    quote! {
+        #doc
+        #[doc = ""]
+        #[doc = " # Errors"]
+        #[doc = ""]
+        #[doc = " If an argument fails conversion or validation."]
+        #vis #asyncness #unsafety fn #try_name #generics(#args) -> #RESULT<#ret, #error> #where_clause {
+            #unpack
+            #body
+        }
+
+        #panicking
+    }
+}
+
+/// Check the arguments & construct the value, evaluating to a `Result`
+fn make_fallible_body(
+    opts: &ContainerOptions,
+    variant: Option<&Ident>,
+    fields: &Fields,
+    error_ident: Option<&Ident>,
+    mode: ArgsMode,
+) -> TokenStream {
+    let checks = error_ident
+        .filter(move |_| fields.has_checks())
+        .map(move |ident| make_arg_checks(fields, ident, mode));
+    let value = apply_post(opts, make_fn_body(opts, variant, fields, mode));
+
+    if let Some(ref validate) = opts.validate {
+        let wrapped_constructed = wrap_return(opts, fields, quote!(__constructed));
+        quote! {
+            #checks
+            let __constructed = #value;
+            (#validate)(&__constructed)?;
+            #RESULT::Ok(#wrapped_constructed)
+        }
+    } else {
+        let value = wrap_return(opts, fields, value);
+        quote! {
+            #checks
+            #RESULT::Ok(#value)
+        }
+    }
+}
+
+/// Pass the constructed value through the `post` hook if there is one
+fn apply_post(opts: &ContainerOptions, value: TokenStream) -> TokenStream {
+    if let Some(ref post) = opts.post {
+        post.wrap_value(value)
+    } else {
+        value
+    }
+}
+
+/// `Self`, wrapped in the returned pointer if there is one
+fn make_return_type(opts: &ContainerOptions, fields: &Fields) -> TokenStream {
+    wrap_return_type(opts, fields, quote!(Self))
+}
+
+/// The pointer the constructed value gets wrapped in: the `returns` option's or, if there's a
+/// `weak_self` field, `Arc`
+fn resolve_return_wrapper(opts: &ContainerOptions, fields: &Fields) -> Option<ReturnWrapper> {
+    match opts.returns {
+        Some(ref wrapper) => Some(wrapper.clone()),
+        None if fields.weak_self().is_some() => Some(ReturnWrapper::arc()),
+        None => None,
+    }
+}
+
+fn wrap_return_type(opts: &ContainerOptions, fields: &Fields, ty: impl ToTokens) -> TokenStream {
+    if let Some(wrapper) = resolve_return_wrapper(opts, fields) {
+        wrapper.wrap_type(ty)
+    } else {
+        ty.into_token_stream()
+    }
+}
+
+/// Wrap the constructed value in the returned pointer if there is one
+fn wrap_return(opts: &ContainerOptions, fields: &Fields, value: TokenStream) -> TokenStream {
+    let Some(wrapper) = resolve_return_wrapper(opts, fields) else {
+        return value;
+    };
+
+    if let Some(field) = fields.weak_self() {
+        wrapper.wrap_cyclic_value(field.resolve_ident(), value)
+    } else {
+        wrapper.wrap_value(value)
+    }
+}
+
+fn make_asyncness(opts: &ContainerOptions) -> TokenStream {
+    if opts.is_async {
+        quote!(async)
+    } else {
+        TokenStream::new()
+    }
+}
+
+/// `.await` if the constructor is `async`
+fn make_await(opts: &ContainerOptions) -> TokenStream {
+    if opts.is_async {
+        quote!(.await)
+    } else {
+        TokenStream::new()
+    }
+}
+
+fn make_where_clause(predicates: impl IntoIterator<Item = TokenStream>) -> TokenStream {
+    let mut predicates = predicates.into_iter().peekable();
+    if predicates.peek().is_none() {
+        TokenStream::new()
+    } else {
+        quote!(where #(#predicates),*)
+    }
+}
+
+/// Name of the generic type parameter for the `try_into` field at the given index
+fn try_into_param(idx: usize) -> Ident {
+    format_ident!("__TryInto{}", idx)
+}
+
+/// Parse & validate every argument that needs it, converting it beforehand if needed, and return
+/// early with all the collected errors.
+fn make_arg_checks(fields: &Fields, error_ident: &Ident, mode: ArgsMode) -> TokenStream {
+    let fields = fields.as_slice();
+    let return_errors =
+        quote!(return #RESULT::Err(::core::convert::From::from(#error_ident { errors: __errors })));
+
+    let parsed_idents = fields
+        .iter()
+        .filter(move |field| field.opts.parse)
+        .map(Field::resolve_ident)
+        .collect::<Vec<_>>();
+
+    let parses = fields.iter().filter(move |field| field.opts.parse).map(move |field| {
+        let ident = field.resolve_ident();
+        let arg_name = ident.to_string();
+        let ty = &field.ty;
+
+        quote! {
+            let #ident = match <#ty as ::core::str::FromStr>::from_str(::core::convert::AsRef::<str>::as_ref(&#ident)) {
+                #RESULT::Ok(v) => ::core::option::Option::Some(v),
+                #RESULT::Err(e) => {
+                    __errors.push((#arg_name, ::std::string::ToString::to_string(&e)));
+                    ::core::option::Option::None
+                }
+            };
+        }
+    });
+
+    let unwrap_parsed = if parsed_idents.is_empty() {
+        TokenStream::new()
+    } else {
+        quote! {
+            let (#(::core::option::Option::Some(#parsed_idents),)*) = (#(#parsed_idents,)*) else {
+                #return_errors;
+            };
+        }
+    };
+
+    // Validators that don't involve parsed arguments run even if parsing fails so every failure
+    // gets reported; the rest need the parsed values
+    let (early_validations, validations) = fields
+        .iter()
+        .filter_map(move |field| Some((field, field.opts.validate.as_ref()?)))
+        .partition::<Vec<_>, _>(|(field, validate)| {
+            !field.opts.parse
+                && referenced_idents(validate)
+                    .iter()
+                    .all(|ident| !parsed_idents.contains(&ident))
+        });
+    let make_validation = move |(field, validate): (&Field, &Expr)| {
+        let ident = field.resolve_ident();
+        let arg_name = ident.to_string();
+
+        let mut tokens = if field.opts.is_converted() && mode == ArgsMode::Raw {
+            let ty = &field.ty;
+            let conversion = make_conversion(field);
+            quote!(let #ident: #ty = #conversion;)
+        } else {
+            TokenStream::new()
+        };
+
+        tokens.extend(match *validate {
+            Expr::Path(_) | Expr::Closure(_) => quote! {
+                if let #RESULT::Err(e) = (#validate)(&#ident) {
+                    __errors.push((#arg_name, ::std::string::ToString::to_string(&e)));
+                }
+            },
+            _ => quote! {
+                if !(#validate) {
+                    __errors.push((#arg_name, ::std::string::String::from("validation failed")));
+                }
+            },
+        });
+
+        tokens
+    };
+    let has_validations = !early_validations.is_empty() || !validations.is_empty();
+    let early_validations = early_validations
+        .into_iter()
+        .map(make_validation)
+        .collect::<TokenStream>();
+    let validations = validations
+        .into_iter()
+        .map(make_validation)
+        .collect::<TokenStream>();
+
+    let check_validations = if has_validations {
+        quote! {
+            if !__errors.is_empty() {
+                #return_errors;
+            }
+        }
+    } else {
+        TokenStream::new()
+    };
+
+    quote! {
+        let mut __errors = ::std::vec::Vec::new();
+        #(#parses)*
+        #early_validations
+        #unwrap_parsed
+        #validations
+        #check_validations
+    }
+}
+
+#[inline]
+fn make_fn_body(
+    opts: &ContainerOptions,
+    variant: Option<&Ident>,
+    fields: &Fields,
+    mode: ArgsMode,
+) -> TokenStream {
+    let mut tokens = quote!(Self);
+
+    if let Some(variant) = variant {
+        tokens.append(Punct::new_joint(':'));
+        tokens.append(Punct::new_joint(':'));
+        tokens.append(variant.clone());
+    }
+
+    let Ok(computed_bindings) = fields.computed_bindings() else {
+        unreachable!("Cyclic `val` fields should've been rejected at parse time");
+    };
+    // Variants share the setup statements, but needn't use everything they bind
+    let setup = opts.setup.as_ref().map(move |setup| {
+        if variant.is_some() {
+            setup.to_shared_tokens()
+        } else {
+            setup.to_token_stream()
+        }
+    });
+    let Some((named, fields)) = fields.to_slice() else {
+        return match setup {
+            Some(setup) => quote!({
+                #setup
+                #tokens
+            }),
+            None => tokens,
+        };
+    };
+
+    // Convert the arguments up front so `val` expressions see the converted values. Checked
+    // arguments get converted beforehand & builder setters convert them when they're set.
+    let mut bindings = fields
+        .iter()
+        .filter(move |field| {
+            mode == ArgsMode::Raw
+                && !field.opts.should_skip_args()
+                && !field.opts.is_checked()
+                && field.opts.is_converted()
+        })
+        .map(move |field| {
+            let ident = field.resolve_ident();
+            let ty = &field.ty;
+            let conversion = make_conversion(field);
+            quote!(let #ident: #ty = #conversion;)
+        })
+        .collect::<TokenStream>();
+    bindings.extend(setup);
+    // Every computed value gets bound before the literal, after the converted args it might
+    // borrow, in dependency order
+    bindings.extend(computed_bindings.iter().map(move |&(idx, is_referenced)| {
+        let field = &fields[idx];
+        let ident = computed_ident(field, is_referenced);
+        let ty = &field.ty;
+        let value = make_computed_value(field, mode);
+        quote!(let #ident: #ty = #value;)
+    }));
+
+    let delim = if named {
+        Delimiter::Brace
+    } else {
+        Delimiter::Parenthesis
+    };
+
+    tokens.append(Group::new(delim, {
+        let iter = fields.iter().enumerate().map(move |(idx, field)| {
+            let mut tokens = TokenStream::new();
+            if named {
+                field.name.to_tokens(&mut tokens);
+                tokens.append(Punct::new_alone(':'));
+            }
+
+            let ident = field.resolve_ident();
+            if let Some(&(_, is_referenced)) = computed_bindings
+                .iter()
+                .find(move |&&(other, _)| other == idx)
+            {
+                computed_ident(field, is_referenced).to_tokens(&mut tokens);
+            } else if field.opts.weak_self {
+                tokens.extend(quote!(::core::clone::Clone::clone(#ident)));
+            } else {
+                ident.to_tokens(&mut tokens);
+            }
+
+            tokens
+        });
+
+        let mut tokens = TokenStream::new();
+        tokens.append_separated(iter, <Token![,]>::default());
+
+        tokens
+    }));
+
+    if bindings.is_empty() {
+        tokens
+    } else {
+        quote!({
+            #bindings
+            #tokens
+        })
+    }
+}
+
+/// A `default` or `val` field's value, letting builders override it
+fn make_computed_value(field: &Field, mode: ArgsMode) -> TokenStream {
+    let init = match field.opts.value {
+        Some(ref value) if !field.opts.default => value.to_token_stream(),
+        _ => quote!(#TRAIT_DEFAULT::default()),
+    };
+
+    if mode == ArgsMode::Builder && field.is_overridable() {
+        let override_ident = override_ident(field);
+        quote! {
+            match #override_ident {
+                ::core::option::Option::Some(v) => v,
+                ::core::option::Option::None => #init,
+            }
+        }
+    } else {
+        init
+    }
+}
+
+/// Convert the field's argument into the field's type
+fn make_conversion(field: &Field) -> TokenStream {
+    let mut tokens = field.resolve_ident().to_token_stream();
+
+    match field.opts.reference(&field.ty) {
+        ArgReference::None => {}
+        ArgReference::Clone => tokens.extend(quote!(.clone())),
+        ArgReference::Borrow { borrowed, as_ref } => {
+            if as_ref {
+                tokens = quote!(::core::convert::AsRef::<#borrowed>::as_ref(&#tokens));
+            }
+            tokens = quote!(::std::borrow::ToOwned::to_owned(#tokens));
+        }
+    }
+
+    if field.opts.into {
+        tokens.extend(quote!(.into()));
+    } else if field.opts.try_into {
+        tokens = quote!(::core::convert::TryInto::try_into(#tokens)?);
+    } else if field.opts.some {
+        tokens = quote!(::core::option::Option::Some(#tokens));
+    } else if let Some(ref default) = field.opts.optional {
+        tokens = quote! {
+            match #tokens {
+                ::core::option::Option::Some(v) => v,
+                ::core::option::Option::None => #default,
+            }
+        };
+    } else if !field.opts.wrap.is_empty() {
+        for wrapper in field.opts.wrap.iter().rev() {
+            tokens = quote!(#wrapper::new(#tokens));
+        }
+    } else if let (true, Some((pointer, _))) = (field.opts.boxed, dyn_pointer(&field.ty)) {
+        tokens = quote!(#pointer::new(#tokens));
+    } else if let Some(ref map) = field.opts.map {
+        let map = annotate_map(map, field.opts.arg_ty.as_ref());
+        tokens = quote!((#map)(#tokens));
+    } else if let Some(ref with) = field.opts.with {
+        tokens = quote!(#with(#tokens));
+    } else if let Some(ref collect) = field.opts.collect {
+        tokens = quote!(::core::iter::IntoIterator::into_iter(#tokens));
+        if let (true, Some((key, value))) = (collect.into, map_entry(&field.ty)) {
+            tokens = quote! {
+                ::core::iter::Iterator::map(#tokens, |(k, v)| (
+                    ::core::convert::Into::<#key>::into(k),
+                    ::core::convert::Into::<#value>::into(v),
+                ))
+            };
+        } else if collect.into {
+            tokens = quote!(::core::iter::Iterator::map(#tokens, ::core::convert::Into::into));
+        }
+        tokens = quote!(::core::iter::Iterator::collect(#tokens));
+    }
+
+    tokens
+}
+
+/// Name of the local variable holding a computed value. Values nothing references get a hidden
+/// name so they can't shadow anything another `val` expression reads, e.g. a fn of the same name.
+fn computed_ident(field: &Field, is_referenced: bool) -> Ident {
+    let ident = field.resolve_ident();
+    if is_referenced {
+        ident.clone()
+    } else {
+        format_ident!("__field_{}", ident)
+    }
+}
+
+/// Name of the local variable holding a builder's override for a field skipped from the args
+fn override_ident(field: &Field) -> Ident {
+    format_ident!("__override_{}", field.resolve_ident())
+}
+
+fn make_args(fields: &Fields, args: &Punctuated<MiniField, impl ToTokens>) -> TokenStream {
+    let mut tokens = TokenStream::new();
+    let iter_args = args.iter().map(MiniField::to_token_stream);
+
+    let iter_fields = fields
+        .as_slice()
+        .iter()
+        .enumerate()
+        .filter_map(move |(idx, field)| {
+            if field.opts.should_skip_args() {
+                return None;
+            }
+
+            let mut tokens = field.resolve_ident().to_token_stream();
+            tokens.append(Punct::new_alone(':'));
+            tokens.extend(make_arg_type(field, idx));
+
+            Some(tokens)
+        });
+
+    tokens.append_separated(iter_args.chain(iter_fields), <Token![,]>::default());
+
+    tokens
+}
+
+/// Give a `map` closure's parameter the argument's type so it doesn't need annotating
+fn annotate_map(map: &Expr, arg_ty: Option<&Type>) -> TokenStream {
+    let (Expr::Closure(closure), Some(arg_ty)) = (map, arg_ty) else {
+        return map.to_token_stream();
+    };
+    if matches!(*arg_ty, Type::ImplTrait(_)) || closure.inputs.len() != 1 {
+        return map.to_token_stream();
+    }
+
+    let mut closure = closure.clone();
+    if let Some(input) = closure.inputs.first_mut() {
+        if !matches!(*input, Pat::Type(_)) {
+            *input = Pat::Type(PatType {
+                attrs: Vec::new(),
+                pat: Box::new(input.clone()),
+                colon_token: <Token![:]>::default(),
+                ty: Box::new(arg_ty.clone()),
+            });
+        }
+    }
+
+    closure.into_token_stream()
+}
+
+/// How the argument of the field at the given index is held where it needs a concrete type, i.e.
+/// in args structs & when deserializing
+struct ConcreteArg {
+    ty: TokenStream,
+
+    /// Whether the constructor takes a reference to the held value
+    by_ref: bool,
+}
+
+/// Arguments that aren't concrete types get held as a type they accept: the field's type for
+/// `into`, `clone` & `borrow`, a `String` for `parse` & a `Vec` of items for `collect`
+fn make_concrete_arg(field: &Field, idx: usize) -> ConcreteArg {
+    let ty = &field.ty;
+    let (ty, by_ref) = match field.opts.reference(ty) {
+        ArgReference::Clone | ArgReference::Borrow { as_ref: false, .. } => {
+            (ty.to_token_stream(), true)
+        }
+        ArgReference::Borrow { as_ref: true, .. } => (ty.to_token_stream(), false),
+        ArgReference::None if field.opts.arg_ty.is_some() => (make_arg_type(field, idx), false),
+        ArgReference::None if field.opts.into => (ty.to_token_stream(), false),
+        ArgReference::None if field.opts.parse => (quote!(::std::string::String), false),
+        ArgReference::None => match (&field.opts.collect, collection_item(ty)) {
+            (Some(_), Some(item)) => (quote!(::std::vec::Vec<#item>), false),
+            _ => (make_arg_type(field, idx), false),
+        },
+    };
+
+    ConcreteArg { ty, by_ref }
+}
+
+/// The argument type for the field at the given index
+fn make_arg_type(field: &Field, idx: usize) -> TokenStream {
+    let mut tokens = TokenStream::new();
+
+    match field.opts.reference(&field.ty) {
+        ArgReference::None => {}
+        ArgReference::Clone => tokens.append(Punct::new_joint('&')),
+        ArgReference::Borrow {
+            borrowed,
+            as_ref: false,
+        } => return quote!(&#borrowed),
+        ArgReference::Borrow {
+            borrowed,
+            as_ref: true,
+        } => return quote!(impl ::core::convert::AsRef<#borrowed>),
+    }
+
+    if let Some(ref arg_ty) = field.opts.arg_ty {
+        arg_ty.to_tokens(&mut tokens);
+    } else if field.opts.try_into {
+        tokens.append(try_into_param(idx));
+    } else if field.opts.parse {
+        tokens.extend(quote!(impl ::core::convert::AsRef<str>));
+    } else if field.opts.into {
+        let ty = &field.ty;
+        tokens.extend(quote!(impl ::core::convert::Into<#ty>));
+    } else if let (true, Some(inner)) = (field.opts.some, option_inner(&field.ty)) {
+        inner.to_tokens(&mut tokens);
+    } else if field.opts.optional.is_some() {
+        let ty = &field.ty;
+        tokens.extend(quote!(::core::option::Option<#ty>));
+    } else if let (false, Some(inner)) = (
+        field.opts.wrap.is_empty(),
+        unwrapped_ty(&field.ty, field.opts.wrap.len()),
+    ) {
+        inner.to_tokens(&mut tokens);
+    } else if let (Some(collect), Some(item)) = (&field.opts.collect, collection_item(&field.ty)) {
+        tokens.extend(
+            if let (true, Some((key, value))) = (collect.into, map_entry(&field.ty)) {
+                quote! {
+                    impl ::core::iter::IntoIterator<Item = (
+                        impl ::core::convert::Into<#key>,
+                        impl ::core::convert::Into<#value>,
+                    )>
+                }
+            } else if collect.into {
+                quote!(impl ::core::iter::IntoIterator<Item = impl ::core::convert::Into<#item>>)
+            } else {
+                quote!(impl ::core::iter::IntoIterator<Item = #item>)
+            },
+        );
+    } else if let (true, Some((_, bounds))) = (field.opts.boxed, dyn_pointer(&field.ty)) {
+        let is_bounded = bounds
+            .iter()
+            .any(move |bound| matches!(bound, TypeParamBound::Lifetime(_)));
+
+        tokens.extend(if is_bounded {
+            quote!(impl #bounds)
+        } else {
+            quote!(impl #bounds + 'static)
+        });
+    } else {
+        field.ty.to_tokens(&mut tokens);
+    }
+
+    tokens
+}
+
+fn make_arg_names(fields: &Fields, args: &Punctuated<MiniField, impl ToTokens>) -> TokenStream {
+    let iter_args = args.iter().map(move |arg| &arg.ident);
+    let iter_fields = fields
+        .as_slice()
+        .iter()
+        .filter(move |field| !field.opts.should_skip_args())
+        .map(Field::resolve_ident);
+
+    let mut tokens = TokenStream::new();
+    tokens.append_separated(iter_args.chain(iter_fields), <Token![,]>::default());
+
+    tokens
+}
@@ -0,0 +1,336 @@
+use proc_macro2::{Ident, TokenStream};
+use quote::{format_ident, quote, ToTokens};
+use syn::{GenericParam, Generics, Visibility};
+
+use super::builder::{collect_slots, make_setter, Builder, Slot};
+use super::{
+    apply_post, make_asyncness, make_await, make_fallible_body, make_fn_body, make_return_type,
+    make_where_clause, wrap_return, wrap_return_type, ArgsMode, PHANTOM_DATA, RESULT,
+};
+use crate::options::ContainerOptions;
+use crate::types::Fields;
+
+/// Generates a builder whose required arguments are tracked via its type parameters: an unset
+/// argument's parameter is `()` and a set one's is `(T,)`, so `build()` only exists once every
+/// argument has been set.
+pub(super) fn make_typestate_builder(
+    struct_name: &Ident,
+    vis: &Visibility,
+    generics: &Generics,
+    opts: &ContainerOptions,
+    fields: &Fields,
+    error_ident: Option<&Ident>,
+) -> Builder {
+    let builder_ident = format_ident!("{}Builder", struct_name);
+    let (_, ty_generics, where_clause) = generics.split_for_impl();
+    let slots = collect_slots(opts, fields);
+    let ctx = Ctx {
+        builder_ident: &builder_ident,
+        generics,
+        slots: &slots,
+        fn_vis: if let Some(vis) = &opts.vis {
+            vis.to_token_stream()
+        } else {
+            quote!(pub)
+        },
+        fn_where: make_where_clause(opts.bounds.iter().map(ToTokens::to_token_stream)),
+        states: (0..slots.len())
+            .filter(|&idx| slots[idx].override_ident.is_none())
+            .map(move |idx| (idx, format_ident!("__State{}", idx)))
+            .collect(),
+    };
+
+    let set = ctx.set_type();
+
+    let ret = wrap_return_type(opts, fields, quote!(#struct_name #ty_generics));
+    let (error, ret) = if opts.checks_value() || fields.has_checks() {
+        let error = if let Some(error) = &opts.error {
+            error.to_token_stream()
+        } else {
+            error_ident.to_token_stream()
+        };
+        (Some(error.clone()), quote!(#RESULT<#ret, #error>))
+    } else {
+        (None, ret)
+    };
+
+    let inherent = ctx.make_inherent(struct_name, opts, fields, error_ident, error.as_ref());
+
+    let struct_generics = {
+        let mut out = generics.clone();
+        out.params.extend(ctx.states.iter().map(move |(_, state)| {
+            let param: GenericParam = syn::parse_quote!(#state = ());
+            param
+        }));
+        out
+    };
+
+    let struct_fields = slots.iter().map(|slot| {
+        let Slot { ident, ty, .. } = slot;
+        if let Some(state) = ctx.state_of(slot) {
+            quote!(#ident: #state)
+        } else {
+            quote!(#ident: ::core::option::Option<#ty>)
+        }
+    });
+
+    let all_states = ctx.impl_generics(None);
+    let (all_impl, _, all_where) = all_states.split_for_impl();
+    let any_state = ctx.builder_type(|slot| ctx.state_of(slot).to_token_stream());
+    let optional_setters = slots
+        .iter()
+        .filter(move |slot| slot.override_ident.is_some())
+        .map(|slot| make_setter(slot, &ctx.fn_vis, &ctx.fn_where));
+
+    let required_setters = ctx
+        .states
+        .iter()
+        .map(|&(idx, _)| ctx.make_required_setter(idx));
+
+    let (impl_generics, _, _) = generics.split_for_impl();
+    let builder_doc = format!(
+        "Builder for [`{struct_name}`] whose type parameters track which arguments have been set."
+    );
+    let build_doc = format!("Constructs the [`{struct_name}`].");
+    let build_errors_doc = if error.is_some() {
+        quote! {
+            #[doc = ""]
+            #[doc = " # Errors"]
+            #[doc = ""]
+            #[doc = " If the constructor fails."]
+        }
+    } else {
+        TokenStream::new()
+    };
+
+    let (fn_vis, fn_where) = (&ctx.fn_vis, &ctx.fn_where);
+    let (asyncness, await_) = (make_asyncness(opts), make_await(opts));
+    let items = quote! {
+        #[doc = #builder_doc]
+        #vis struct #builder_ident #struct_generics #where_clause {
+            #(#struct_fields,)*
+            __marker: #PHANTOM_DATA<fn() -> #struct_name #ty_generics>,
+        }
+
+        #[automatically_derived]
+        #[allow(clippy::all)]
+        impl #all_impl #any_state #all_where {
+            #(#optional_setters)*
+        }
+
+        #(#required_setters)*
+
+        #[automatically_derived]
+        #[allow(clippy::all)]
+        impl #impl_generics #set #where_clause {
+            #[doc = #build_doc]
+            #build_errors_doc
+            #fn_vis #asyncness fn build(self) -> #ret #fn_where {
+                #struct_name::__build(self) #await_
+            }
+        }
+    };
+
+    Builder { inherent, items }
+}
+
+struct Ctx<'a> {
+    builder_ident: &'a Ident,
+    generics: &'a Generics,
+    slots: &'a [Slot<'a>],
+    fn_vis: TokenStream,
+    fn_where: TokenStream,
+
+    /// Slot index & type parameter of every required argument
+    states: Vec<(usize, Ident)>,
+}
+
+impl Ctx<'_> {
+    fn state_of(&self, slot: &Slot) -> Option<&Ident> {
+        let (_, state) = self
+            .states
+            .iter()
+            .find(move |(idx, _)| self.slots[*idx].ident == slot.ident)?;
+        Some(state)
+    }
+
+    /// The builder's type with every required argument's state given by `state`
+    fn builder_type(&self, mut state: impl FnMut(&Slot) -> TokenStream) -> TokenStream {
+        let builder_ident = self.builder_ident;
+        let args = self.generics.params.iter().map(move |param| match *param {
+            GenericParam::Lifetime(ref p) => p.lifetime.to_token_stream(),
+            GenericParam::Type(ref p) => p.ident.to_token_stream(),
+            GenericParam::Const(ref p) => p.ident.to_token_stream(),
+        });
+        let states = self
+            .states
+            .iter()
+            .map(move |&(idx, _)| state(&self.slots[idx]))
+            .collect::<Vec<_>>();
+
+        quote!(#builder_ident<#(#args,)* #(#states),*>)
+    }
+
+    /// The builder's type with every required argument set
+    fn set_type(&self) -> TokenStream {
+        self.builder_type(move |slot| {
+            let ty = &slot.ty;
+            quote!((#ty,))
+        })
+    }
+
+    /// The container's generics plus every state parameter other than `except`'s
+    fn impl_generics(&self, except: Option<usize>) -> Generics {
+        let mut out = self.generics.clone();
+        for (idx, state) in &self.states {
+            if Some(*idx) != except {
+                out.params.push(syn::parse_quote!(#state));
+            }
+        }
+        out
+    }
+
+    /// `Self::builder()` & the hidden fn `build()` delegates to
+    fn make_inherent(
+        &self,
+        struct_name: &Ident,
+        opts: &ContainerOptions,
+        fields: &Fields,
+        error_ident: Option<&Ident>,
+        error: Option<&TokenStream>,
+    ) -> TokenStream {
+        let Self {
+            builder_ident,
+            slots,
+            fn_vis,
+            fn_where,
+            ..
+        } = self;
+        let asyncness = make_asyncness(opts);
+        let self_ret = make_return_type(opts, fields);
+        let unset = self.builder_type(move |_| quote!(()));
+        let set = self.set_type();
+
+        let inits = slots.iter().map(move |slot| {
+            let ident = slot.ident;
+            if slot.override_ident.is_some() {
+                quote!(#ident: ::core::option::Option::None)
+            } else {
+                quote!(#ident: ())
+            }
+        });
+
+        let destructure = slots.iter().map(move |slot| {
+            let ident = slot.ident;
+            if let Some(ref override_ident) = slot.override_ident {
+                quote!(#ident: #override_ident)
+            } else {
+                quote!(#ident: (#ident,))
+            }
+        });
+
+        let (build_ret, body) = if let Some(error) = error {
+            (
+                quote!(#RESULT<#self_ret, #error>),
+                make_fallible_body(opts, None, fields, error_ident, ArgsMode::Builder),
+            )
+        } else {
+            (
+                self_ret,
+                wrap_return(
+                    opts,
+                    fields,
+                    apply_post(opts, make_fn_body(opts, None, fields, ArgsMode::Builder)),
+                ),
+            )
+        };
+        let builder_doc = format!("Creates a builder for [`{struct_name}`].");
+
+        quote! {
+            #[doc = #builder_doc]
+            #[inline]
+            #fn_vis fn builder() -> #unset #fn_where {
+                #builder_ident {
+                    #(#inits,)*
+                    __marker: #PHANTOM_DATA,
+                }
+            }
+
+            #[doc(hidden)]
+            #asyncness fn __build(builder: #set) -> #build_ret #fn_where {
+                let #builder_ident { #(#destructure,)* __marker: _ } = builder;
+                #body
+            }
+        }
+    }
+
+    fn make_required_setter(&self, idx: usize) -> TokenStream {
+        let (fn_vis, fn_where) = (&self.fn_vis, &self.fn_where);
+        let slot = &self.slots[idx];
+        let ident = slot.ident;
+        let setter = &slot.setter;
+        let (doc, generics, arg_ty, store) =
+            (&setter.doc, &setter.generics, &setter.arg_ty, &setter.store);
+
+        let impl_generics = self.impl_generics(Some(idx));
+        let (impl_generics, _, where_clause) = impl_generics.split_for_impl();
+        let state_for = move |set: bool| {
+            self.builder_type(move |other| {
+                if other.ident != ident {
+                    self.state_of(other).to_token_stream()
+                } else if set {
+                    let ty = &other.ty;
+                    quote!((#ty,))
+                } else {
+                    quote!(())
+                }
+            })
+        };
+        let (target, output) = (state_for(false), state_for(true));
+
+        let builder_ident = self.builder_ident;
+        let moves = self.slots.iter().map(move |other| {
+            let other = other.ident;
+            if other == ident {
+                quote!(#ident: (#store,))
+            } else {
+                quote!(#other: self.#other)
+            }
+        });
+        let value = quote! {
+            #builder_ident {
+                #(#moves,)*
+                __marker: #PHANTOM_DATA,
+            }
+        };
+
+        let method = if let Some(ref error) = setter.fallible_error {
+            quote! {
+                #[doc = #doc]
+                #[doc = ""]
+                #[doc = " # Errors"]
+                #[doc = ""]
+                #[doc = " If the conversion fails."]
+                #fn_vis fn #ident #generics(self, #ident: #arg_ty) -> #RESULT<#output, #error> #fn_where {
+                    #RESULT::Ok(#value)
+                }
+            }
+        } else {
+            quote! {
+                #[doc = #doc]
+                #[must_use]
+                #fn_vis fn #ident #generics(self, #ident: #arg_ty) -> #output #fn_where {
+                    #value
+                }
+            }
+        };
+
+        quote! {
+            #[automatically_derived]
+            #[allow(clippy::all)]
+            impl #impl_generics #target #where_clause {
+                #method
+            }
+        }
+    }
+}
@@ -0,0 +1,63 @@
+use macroific::attr_parse::{ParseOption, ValueSyntax};
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::{parenthesized, Ident, Path, Token, Visibility};
+
+/// Options of the `args_struct` container option: `args_struct` or
+/// `args_struct(name(Ident), vis(visibility), derive(Trait, ...))`
+#[derive(Default, Clone)]
+pub struct ArgsStructOptions {
+    /// The struct's name; `{StructName}Args` if omitted
+    pub name: Option<Ident>,
+
+    /// The struct's visibility; the container's if omitted
+    pub vis: Option<Visibility>,
+
+    /// Traits to derive on the struct
+    pub derive: Punctuated<Path, Token![,]>,
+}
+
+impl ParseOption for ArgsStructOptions {
+    fn from_stream(input: ParseStream) -> syn::Result<Self> {
+        let Some(syntax) = ValueSyntax::from_stream(input) else {
+            return Ok(Self::default());
+        };
+
+        if let Some(content) = syntax.parse_token(input)? {
+            content.parse()
+        } else {
+            input.parse()
+        }
+    }
+}
+
+impl Parse for ArgsStructOptions {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut out = Self::default();
+
+        while !input.is_empty() {
+            let ident = input.parse::<Ident>()?;
+            let content;
+            parenthesized!(content in input);
+
+            if ident == "name" && out.name.is_none() {
+                out.name = Some(content.parse()?);
+            } else if ident == "vis" && out.vis.is_none() {
+                out.vis = Some(content.parse()?);
+            } else if ident == "derive" && out.derive.is_empty() {
+                out.derive = content.parse_terminated(Path::parse, Token![,])?;
+            } else {
+                return Err(syn::Error::new_spanned(
+                    ident,
+                    "Expected `name`, `vis` or `derive`",
+                ));
+            }
+
+            if !input.is_empty() {
+                input.parse::<Token![,]>()?;
+            }
+        }
+
+        Ok(out)
+    }
+}
@@ -0,0 +1,51 @@
+use macroific::attr_parse::{ParseOption, ValueSyntax};
+use syn::parse::{Parse, ParseStream};
+use syn::{Ident, Token, Type};
+
+/// Options of the `borrow` field option: `borrow`, `borrow(Type)`, `borrow(as_ref)` or
+/// `borrow(Type, as_ref)`
+#[derive(Default, Clone)]
+pub struct BorrowOptions {
+    /// The borrowed type; inferred from the field's type if omitted
+    pub ty: Option<Type>,
+
+    /// Take `impl AsRef<Borrowed>` instead of `&Borrowed`
+    pub as_ref: bool,
+}
+
+impl ParseOption for BorrowOptions {
+    fn from_stream(input: ParseStream) -> syn::Result<Self> {
+        let Some(syntax) = ValueSyntax::from_stream(input) else {
+            return Ok(Self::default());
+        };
+
+        if let Some(content) = syntax.parse_token(input)? {
+            content.parse()
+        } else {
+            input.parse()
+        }
+    }
+}
+
+impl Parse for BorrowOptions {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut out = Self::default();
+
+        while !input.is_empty() {
+            if input.peek(Ident) && input.fork().parse::<Ident>()? == "as_ref" {
+                input.parse::<Ident>()?;
+                out.as_ref = true;
+            } else if out.ty.is_none() {
+                out.ty = Some(input.parse()?);
+            } else {
+                return Err(input.error("Expected `as_ref`"));
+            }
+
+            if !input.is_empty() {
+                input.parse::<Token![,]>()?;
+            }
+        }
+
+        Ok(out)
+    }
+}
@@ -0,0 +1,35 @@
+use macroific::attr_parse::{ParseOption, ValueSyntax};
+use syn::parse::{Parse, ParseStream};
+use syn::Ident;
+
+/// Options of the `collect` field option: `collect` or `collect(into)`
+#[derive(Default, Clone)]
+pub struct CollectOptions {
+    /// Convert each item via `Into`
+    pub into: bool,
+}
+
+impl ParseOption for CollectOptions {
+    fn from_stream(input: ParseStream) -> syn::Result<Self> {
+        let Some(syntax) = ValueSyntax::from_stream(input) else {
+            return Ok(Self::default());
+        };
+
+        if let Some(content) = syntax.parse_token(input)? {
+            content.parse()
+        } else {
+            input.parse()
+        }
+    }
+}
+
+impl Parse for CollectOptions {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let ident = input.parse::<Ident>()?;
+        if ident == "into" {
+            Ok(Self { into: true })
+        } else {
+            Err(syn::Error::new_spanned(ident, "Expected `into`"))
+        }
+    }
+}
@@ -0,0 +1,9 @@
+use super::Fields;
+use crate::options::VariantOptions;
+use proc_macro2::Ident;
+
+pub struct EnumVariant {
+    pub ident: Ident,
+    pub fields: Fields,
+    pub opts: VariantOptions,
+}
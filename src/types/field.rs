@@ -2,8 +2,12 @@ use crate::options::FieldOptions;
 use crate::parse::create_span;
 use crate::{FmtTuple, ATTR_NAME};
 use macroific::prelude::*;
-use proc_macro2::Ident;
+use proc_macro2::{Ident, Span, TokenStream};
+use quote::{quote, ToTokens};
+use syn::punctuated::Punctuated;
+use syn::{Error, GenericArgument, Path, PathArguments, Token, Type, TypeParamBound};
 
+#[derive(Clone)]
 pub struct Field {
     pub name: Ident,
     pub opts: FieldOptions,
@@ -19,6 +23,19 @@ impl Field {
         }
     }
 
+    /// Whether the field's value comes from `default` or `val` rather than an argument
+    pub fn is_computed(&self) -> bool {
+        !self.opts.weak_self && (self.opts.default || self.opts.value.is_some())
+    }
+
+    /// Whether builders can override the field's computed value. The `Weak` handle can only come
+    /// from the pointer being constructed & there's no point in overriding defaulted markers.
+    pub fn is_overridable(&self) -> bool {
+        self.opts.should_skip_args()
+            && !self.opts.weak_self
+            && !(self.opts.default && is_marker(&self.ty))
+    }
+
     pub fn collect<F, It>(iter: It) -> syn::Result<F>
     where
         F: FromIterator<Field>,
@@ -26,17 +43,284 @@ impl Field {
     {
         iter.into_iter()
             .map(move |(attrs, ident, ty)| {
+                let span = create_span(&attrs);
+                let mut opts = FieldOptions::from_iter_named(ATTR_NAME, span, attrs)?;
+
+                verify_opts(&opts, &ty, span)?;
+
+                // Markers have nothing to pass in - default them unless told otherwise
+                if !opts.arg
+                    && is_marker(&ty)
+                    && !opts.should_skip_args()
+                    && opts.validate.is_none()
+                    && opts.conversions().is_empty()
+                {
+                    opts.default = true;
+                }
+
                 Ok(Field {
                     name: match ident {
                         Ok(ident) | Err(ident) => ident,
                     },
-                    opts: {
-                        let span = create_span(&attrs);
-                        FieldOptions::from_iter_named(ATTR_NAME, span, attrs)
-                    }?,
+                    opts,
                     ty,
                 })
             })
             .collect()
     }
 }
+
+fn verify_opts(opts: &FieldOptions, ty: &Type, span: Span) -> syn::Result<()> {
+    let conversions = opts.conversions();
+
+    if opts.arg && opts.should_skip_args() {
+        return Err(Error::new(
+            span,
+            "`arg` cannot be combined with `default`, `val` or `weak_self`",
+        ));
+    }
+
+    if opts.weak_self
+        && (opts.default
+            || opts.value.is_some()
+            || opts.validate.is_some()
+            || !conversions.is_empty())
+    {
+        return Err(Error::new(
+            span,
+            "`weak_self` can only be combined with the `name` option",
+        ));
+    }
+
+    if opts.should_skip_args() {
+        let arg_opt = if opts.validate.is_some() {
+            "validate"
+        } else if let Some(conversion) = conversions
+            .iter()
+            .find(move |&&c| c != "into" && c != "clone")
+        {
+            conversion
+        } else {
+            return Ok(());
+        };
+
+        return Err(Error::new(
+            span,
+            format!("`{arg_opt}` can only be used on fields that are constructor arguments"),
+        ));
+    }
+
+    if opts.try_into && (opts.into || opts.clone) {
+        return Err(Error::new(
+            span,
+            "`try_into` cannot be combined with `into` or `clone`",
+        ));
+    }
+
+    if opts.parse && (opts.into || opts.clone || opts.try_into) {
+        return Err(Error::new(
+            span,
+            "`parse` cannot be combined with `into`, `try_into` or `clone`",
+        ));
+    }
+
+    if let Some(exclusive) = conversions.iter().find(move |c| {
+        [
+            "some", "optional", "wrap", "boxed", "map", "with", "borrow", "collect",
+        ]
+        .contains(c)
+    }) {
+        if let Some(other) = conversions.iter().find(move |c| *c != exclusive) {
+            return Err(Error::new(
+                span,
+                format!("`{exclusive}` cannot be combined with `{other}`"),
+            ));
+        }
+    }
+
+    if opts.arg_ty.is_some() != (opts.map.is_some() || opts.with.is_some()) {
+        return Err(Error::new(
+            span,
+            "`arg_ty` must be combined with either `map` or `with`",
+        ));
+    }
+
+    verify_ty(opts, ty)
+}
+
+/// Checks that the field's type supports its conversion
+fn verify_ty(opts: &FieldOptions, ty: &Type) -> syn::Result<()> {
+    if opts.some && option_inner(ty).is_none() {
+        return Err(Error::new_spanned(
+            ty,
+            "`some` can only be used on `Option<T>` fields",
+        ));
+    }
+
+    if !opts.wrap.is_empty() && unwrapped_ty(ty, opts.wrap.len()).is_none() {
+        return Err(Error::new_spanned(
+            ty,
+            format!(
+                "Expected a type nested {} levels deep, e.g. `Arc<Mutex<T>>` for `wrap(Arc, Mutex)`",
+                opts.wrap.len()
+            ),
+        ));
+    }
+
+    if opts
+        .borrow
+        .as_ref()
+        .is_some_and(move |b| b.ty.is_none() && borrowed_ty(ty).is_none())
+    {
+        return Err(Error::new_spanned(
+            ty,
+            "Couldn't infer the borrowed type - specify it via `borrow(Type)`",
+        ));
+    }
+
+    if opts.collect.is_some() && collection_item(ty).is_none() {
+        return Err(Error::new_spanned(
+            ty,
+            "Couldn't infer the collection's item type",
+        ));
+    }
+
+    if opts.boxed && dyn_pointer(ty).is_none() {
+        return Err(Error::new_spanned(
+            ty,
+            "`boxed` can only be used on pointers to trait objects, e.g. `Box<dyn Trait>`",
+        ));
+    }
+
+    Ok(())
+}
+
+/// The type an owned type borrows as, e.g. `str` for `String`
+pub fn borrowed_ty(ty: &Type) -> Option<TokenStream> {
+    let Type::Path(ref path) = *ty else {
+        return None;
+    };
+
+    Some(
+        match path.path.segments.last()?.ident.to_string().as_str() {
+            "String" => quote!(str),
+            "PathBuf" => quote!(::std::path::Path),
+            "OsString" => quote!(::std::ffi::OsStr),
+            "CString" => quote!(::std::ffi::CStr),
+            "Vec" => {
+                let inner = generic_inner(ty)?;
+                quote!([#inner])
+            }
+            _ => return None,
+        },
+    )
+}
+
+/// The pointer's path & the trait object's bounds of a `Box<dyn Trait>`-like type
+pub fn dyn_pointer(ty: &Type) -> Option<(Path, &Punctuated<TypeParamBound, Token![+]>)> {
+    let Type::TraitObject(ref object) = *generic_inner(ty)? else {
+        return None;
+    };
+    let Type::Path(ref path) = *ty else {
+        return None;
+    };
+
+    let mut pointer = path.path.clone();
+    pointer.segments.last_mut()?.arguments = PathArguments::None;
+
+    Some((pointer, &object.bounds))
+}
+
+/// Whether the type is a `PhantomData` or `PhantomPinned` marker
+pub fn is_marker(ty: &Type) -> bool {
+    let Type::Path(ref path) = *ty else {
+        return false;
+    };
+
+    path.path
+        .segments
+        .last()
+        .is_some_and(move |seg| seg.ident == "PhantomData" || seg.ident == "PhantomPinned")
+}
+
+/// The `T` in an `Option<T>` type
+pub fn option_inner(ty: &Type) -> Option<&Type> {
+    let Type::Path(ref path) = *ty else {
+        return None;
+    };
+
+    if path.path.segments.last()?.ident == "Option" {
+        generic_inner(ty)
+    } else {
+        None
+    }
+}
+
+/// The type `wrap` wrappers get peeled off of, e.g. `T` in `Arc<Mutex<T>>` for `wrap(Arc, Mutex)`
+pub fn unwrapped_ty(ty: &Type, depth: usize) -> Option<&Type> {
+    (0..depth).try_fold(ty, move |ty, _| generic_inner(ty))
+}
+
+/// The 1st generic type argument of a path type, e.g. `T` in `Foo<T, U>`
+fn generic_inner(ty: &Type) -> Option<&Type> {
+    generic_types(ty)?.next()
+}
+
+/// The generic type arguments of a path type
+fn generic_types(ty: &Type) -> Option<impl Iterator<Item = &Type>> {
+    let Type::Path(ref path) = *ty else {
+        return None;
+    };
+    if path.qself.is_some() {
+        return None;
+    }
+
+    let PathArguments::AngleBracketed(ref args) = path.path.segments.last()?.arguments else {
+        return None;
+    };
+
+    Some(args.args.iter().filter_map(move |arg| match *arg {
+        GenericArgument::Type(ref ty) => Some(ty),
+        _ => None,
+    }))
+}
+
+/// The item type a collection gets collected from: `(K, V)` for maps, `char` for `String` and the
+/// 1st generic type argument otherwise
+pub fn collection_item(ty: &Type) -> Option<TokenStream> {
+    let Type::Path(ref path) = *ty else {
+        return None;
+    };
+    let ident = &path.path.segments.last()?.ident;
+
+    if ident == "String" {
+        return Some(quote!(char));
+    }
+
+    if ident.to_string().ends_with("Map") {
+        let (key, value) = map_entry(ty)?;
+        Some(quote!((#key, #value)))
+    } else {
+        generic_inner(ty).map(ToTokens::to_token_stream)
+    }
+}
+
+/// The key & value types of a map, i.e. a path type whose name ends with `Map`
+pub fn map_entry(ty: &Type) -> Option<(&Type, &Type)> {
+    let Type::Path(ref path) = *ty else {
+        return None;
+    };
+    if !path
+        .path
+        .segments
+        .last()?
+        .ident
+        .to_string()
+        .ends_with("Map")
+    {
+        return None;
+    }
+
+    let mut types = generic_types(ty)?;
+    Some((types.next()?, types.next()?))
+}
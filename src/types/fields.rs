@@ -1,5 +1,9 @@
-use super::Field;
+use quote::ToTokens;
+use syn::Error;
 
+use super::{referenced_idents, Field};
+
+#[derive(Clone)]
 pub enum Fields {
     Unit,
     Named(Vec<Field>),
@@ -16,13 +20,46 @@ impl Fields {
         }
     }
 
-    /// The 1st tuple element is true if the fields are named, false otherwise.
-    pub fn into_vec(self) -> Option<(bool, Vec<Field>)> {
-        match self {
-            Fields::Unit => None,
-            Fields::Named(fields) => Some((true, fields)),
-            Fields::Unnamed(fields) => Some((false, fields)),
+    /// All the fields; empty for unit structs.
+    pub fn as_slice(&self) -> &[Field] {
+        self.to_slice().map_or(&[], move |(_, fields)| fields)
+    }
+
+    /// Returns true if any of the fields' arguments get checked, requiring the generated error type.
+    pub fn has_checks(&self) -> bool {
+        self.as_slice().iter().any(|f| f.opts.is_checked())
+    }
+
+    /// Returns true if any of the fields can fail to initialise.
+    pub fn is_fallible(&self) -> bool {
+        self.as_slice().iter().any(|f| f.opts.is_fallible())
+    }
+
+    /// Returns true if any of the fields' arguments get validated.
+    pub fn has_validators(&self) -> bool {
+        self.as_slice().iter().any(|f| f.opts.validate.is_some())
+    }
+
+    /// A copy of the fields with their validators removed.
+    pub fn without_validators(&self) -> Self {
+        let mut out = self.clone();
+        if let Fields::Named(ref mut fields) | Fields::Unnamed(ref mut fields) = out {
+            for field in fields {
+                field.opts.validate = None;
+            }
         }
+
+        out
+    }
+
+    /// Number of fields with the `try_into` option.
+    pub fn try_into_count(&self) -> usize {
+        self.as_slice().iter().filter(|f| f.opts.try_into).count()
+    }
+
+    /// The field holding the constructed value's `Weak` handle, if any.
+    pub fn weak_self(&self) -> Option<&Field> {
+        self.as_slice().iter().find(|f| f.opts.weak_self)
     }
 
     /// Returns true if there will be no constructor arguments.
@@ -37,4 +74,96 @@ impl Fields {
 
         true
     }
+
+    /// Indices of the fields with computed values, i.e. `val` & `default` ones, in the order they
+    /// need to be initialised in for every `val` expression to come after the fields it
+    /// references. Fields are otherwise kept in declaration order. The 2nd tuple element is true if
+    /// another `val` expression references the field. Fails if the references are cyclic.
+    pub fn computed_bindings(&self) -> syn::Result<Vec<(usize, bool)>> {
+        let fields = self.as_slice();
+        let computed = fields
+            .iter()
+            .enumerate()
+            .filter(move |(_, f)| f.is_computed())
+            .map(move |(idx, _)| idx)
+            .collect::<Vec<_>>();
+
+        let deps = computed
+            .iter()
+            .map(|&idx| {
+                let Some(ref value) = fields[idx].opts.value else {
+                    return Vec::new();
+                };
+
+                let referenced = referenced_idents(value);
+
+                // A field referencing its own name refers to whatever's in scope before it
+                computed
+                    .iter()
+                    .copied()
+                    .filter(move |&other| {
+                        other != idx && referenced.contains(fields[other].resolve_ident())
+                    })
+                    .collect()
+            })
+            .collect::<Vec<Vec<_>>>();
+
+        let mut order = Vec::with_capacity(computed.len());
+        while order.len() < computed.len() {
+            let next = (0..computed.len()).find(|&pos| {
+                !order.contains(&computed[pos]) && deps[pos].iter().all(|dep| order.contains(dep))
+            });
+
+            if let Some(pos) = next {
+                order.push(computed[pos]);
+            } else {
+                return Err(cycle_error(fields, &computed, &deps, &order));
+            }
+        }
+
+        Ok(order
+            .into_iter()
+            .map(move |idx| (idx, deps.iter().flatten().any(move |&dep| dep == idx)))
+            .collect())
+    }
+}
+
+/// Follow the dependencies of the first field that couldn't be ordered until one repeats
+fn cycle_error(
+    fields: &[Field],
+    computed: &[usize],
+    deps: &[Vec<usize>],
+    order: &[usize],
+) -> Error {
+    let pos_of = move |idx: usize| computed.iter().position(move |&c| c == idx);
+    let Some(mut pos) = (0..computed.len()).find(|&pos| !order.contains(&computed[pos])) else {
+        unreachable!("A field should be left unordered");
+    };
+
+    let mut path = Vec::new();
+    while !path.contains(&pos) {
+        path.push(pos);
+        let Some(next) = deps[pos]
+            .iter()
+            .find(|dep| !order.contains(dep))
+            .and_then(move |&dep| pos_of(dep))
+        else {
+            break;
+        };
+        pos = next;
+    }
+
+    let start = path.iter().position(move |&p| p == pos).unwrap_or_default();
+    let names = path[start..]
+        .iter()
+        .chain(Some(&pos))
+        .map(move |&p| format!("`{}`", fields[computed[p]].resolve_ident()))
+        .collect::<Vec<_>>()
+        .join(" -> ");
+
+    let field = &fields[computed[pos]];
+    Error::new_spanned(
+        field.opts.value.to_token_stream(),
+        format!("Cyclic dependency between `val` fields: {names}"),
+    )
 }
@@ -1,18 +1,19 @@
-use super::{Field, Fields};
+use super::{EnumVariant, Field, Fields};
 use crate::FmtTuple;
-use proc_macro2::Ident;
 use quote::format_ident;
 use syn::Error;
 
 pub enum FieldsSource {
     Struct(Fields),
-    Enum { variant: Ident, fields: Fields },
+    Enum(Vec<EnumVariant>),
 }
 
 impl FieldsSource {
-    pub(crate) fn fields(&self) -> &Fields {
+    /// Returns true if any of the fields' arguments get checked, requiring the generated error type.
+    pub fn has_checks(&self) -> bool {
         match *self {
-            FieldsSource::Struct(ref fields) | FieldsSource::Enum { ref fields, .. } => fields,
+            FieldsSource::Struct(ref fields) => fields.has_checks(),
+            FieldsSource::Enum(ref variants) => variants.iter().any(|v| v.fields.has_checks()),
         }
     }
 }
@@ -21,11 +22,14 @@ impl TryFrom<syn::Fields> for Fields {
     type Error = Error;
 
     fn try_from(fields: syn::Fields) -> Result<Self, Self::Error> {
-        Ok(match fields {
+        let fields = match fields {
             syn::Fields::Unit => Fields::Unit,
             syn::Fields::Named(f) => Fields::Named(Field::collect(fmt_named(f.named))?),
             syn::Fields::Unnamed(f) => Fields::Unnamed(Field::collect(fmt_unnamed(f.unnamed))?),
-        })
+        };
+        fields.computed_bindings()?;
+
+        Ok(fields)
     }
 }
 
@@ -4,7 +4,7 @@ use quote::ToTokens;
 use syn::parse::{Parse, ParseStream};
 use syn::Token;
 
-#[derive(ParseOption)]
+#[derive(ParseOption, Clone)]
 #[attr_opts(from_parse)]
 pub struct MiniField {
     pub ident: Ident,
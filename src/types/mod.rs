@@ -1,9 +1,27 @@
+mod args_struct_options;
+mod borrow_options;
+mod collect_options;
+mod enum_variant;
 mod field;
 mod fields;
 mod fields_source;
 mod mini_field;
+mod post_hook;
+mod referenced_idents;
+mod return_wrapper;
+mod safety_doc;
+mod setup_block;
 
+pub use args_struct_options::*;
+pub use borrow_options::*;
+pub use collect_options::*;
+pub use enum_variant::*;
 pub use field::*;
 pub use fields::*;
 pub use fields_source::*;
 pub use mini_field::*;
+pub use post_hook::*;
+pub use referenced_idents::*;
+pub use return_wrapper::*;
+pub use safety_doc::*;
+pub use setup_block::*;
@@ -0,0 +1,64 @@
+use macroific::attr_parse::ParseOption;
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::parse::{Parse, ParseStream};
+use syn::{parenthesized, Ident, Path};
+
+/// A fn the constructed value gets passed to before it's returned: `post(path)`,
+/// `post(map(path))` or `post(try_map(path))`
+#[derive(ParseOption, Clone)]
+#[attr_opts(from_parse)]
+pub enum PostHook {
+    /// Called with `&mut Self`
+    Mut(Path),
+
+    /// Called with `Self`, returning `Self`
+    Map(Path),
+
+    /// Called with `Self`, returning `Result<Self, E>`
+    TryMap(Path),
+}
+
+impl PostHook {
+    /// Whether the hook can fail
+    #[inline]
+    pub fn is_fallible(&self) -> bool {
+        matches!(*self, Self::TryMap(_))
+    }
+
+    /// Pass `value` through the hook. Fallible hooks' errors get propagated via `?`.
+    pub fn wrap_value(&self, value: TokenStream) -> TokenStream {
+        match *self {
+            Self::Mut(ref path) => quote!({
+                let mut __constructed = #value;
+                #path(&mut __constructed);
+                __constructed
+            }),
+            Self::Map(ref path) => quote!(#path(#value)),
+            Self::TryMap(ref path) => quote!(#path(#value)?),
+        }
+    }
+}
+
+impl Parse for PostHook {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        if input.peek(Ident) && input.peek2(syn::token::Paren) {
+            let fork = input.fork();
+            let ident = fork.parse::<Ident>()?;
+            if ident == "map" || ident == "try_map" {
+                input.parse::<Ident>()?;
+                let content;
+                parenthesized!(content in input);
+                let path = content.parse()?;
+
+                return Ok(if ident == "map" {
+                    Self::Map(path)
+                } else {
+                    Self::TryMap(path)
+                });
+            }
+        }
+
+        Ok(Self::Mut(input.parse()?))
+    }
+}
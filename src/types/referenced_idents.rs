@@ -0,0 +1,318 @@
+use proc_macro2::{Ident, TokenStream, TokenTree};
+use syn::punctuated::Punctuated;
+use syn::visit::{self, Visit};
+use syn::{
+    Arm, Block, Expr, ExprCall, ExprClosure, ExprForLoop, ExprIf, ExprLet, ExprPath, ExprWhile,
+    Lit, LitStr, Local, Macro, Pat, PatIdent, Path, Token,
+};
+
+/// Macros taking a format string followed by its arguments
+const FORMAT_MACROS: &[&str] = &[
+    "format",
+    "format_args",
+    "print",
+    "println",
+    "eprint",
+    "eprintln",
+    "panic",
+    "unreachable",
+    "todo",
+    "unimplemented",
+];
+
+/// Macros taking a destination, then a format string & its arguments
+const WRITE_MACROS: &[&str] = &["write", "writeln"];
+
+/// Names of the variables the expression reads from its surroundings: single-segment paths &
+/// format string captures that aren't bound by a closure parameter or local `let` inside it.
+/// Paths in call position are taken to be fns.
+pub fn referenced_idents(expr: &Expr) -> Vec<Ident> {
+    let mut visitor = ReferencedIdents::default();
+    visitor.visit_expr(expr);
+
+    visitor.out
+}
+
+#[derive(Default)]
+struct ReferencedIdents {
+    /// Names bound in each scope entered within the expression
+    scopes: Vec<Vec<Ident>>,
+    out: Vec<Ident>,
+}
+
+impl ReferencedIdents {
+    fn is_bound(&self, ident: &Ident) -> bool {
+        self.scopes
+            .iter()
+            .flatten()
+            .any(move |bound| bound == ident)
+    }
+
+    fn reference(&mut self, ident: &Ident) {
+        if !self.is_bound(ident) {
+            self.out.push(ident.clone());
+        }
+    }
+
+    /// Add the pattern's bindings to the innermost scope
+    fn bind(&mut self, pat: &Pat) {
+        let mut bindings = PatBindings::default();
+        bindings.visit_pat(pat);
+
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.extend(bindings.0);
+        } else {
+            self.scopes.push(bindings.0);
+        }
+    }
+
+    fn scoped(&mut self, f: impl FnOnce(&mut Self)) {
+        self.scopes.push(Vec::new());
+        f(self);
+        self.scopes.pop();
+    }
+
+    fn visit_format_args(&mut self, args: &Punctuated<Expr, Token![,]>, fmt_pos: usize) {
+        let mut named = Vec::new();
+        let mut fmt = None;
+
+        for (idx, arg) in args.iter().enumerate() {
+            match *arg {
+                Expr::Lit(ref lit) if idx == fmt_pos => {
+                    if let Lit::Str(ref lit) = lit.lit {
+                        fmt = Some(lit);
+                    }
+                }
+                // Named arguments: the name isn't a variable
+                Expr::Assign(ref assign) if idx > fmt_pos => {
+                    if let Expr::Path(ref path) = *assign.left {
+                        named.extend(single_ident(path).cloned());
+                    }
+                    self.visit_expr(&assign.right);
+                }
+                _ => self.visit_expr(arg),
+            }
+        }
+
+        for ident in fmt.map(format_captures).unwrap_or_default() {
+            if !named.contains(&ident) {
+                self.reference(&ident);
+            }
+        }
+    }
+
+    /// Fallback for macros whose input isn't a list of expressions: every identifier that isn't a
+    /// field access, method call or path segment
+    fn visit_tokens(&mut self, tokens: TokenStream) {
+        let mut after_accessor = false;
+        for token in tokens {
+            match token {
+                TokenTree::Ident(ident) => {
+                    if !after_accessor {
+                        self.reference(&ident);
+                    }
+                    after_accessor = false;
+                }
+                TokenTree::Punct(punct) => {
+                    after_accessor = matches!(punct.as_char(), '.' | ':');
+                }
+                TokenTree::Group(group) => {
+                    self.visit_tokens(group.stream());
+                    after_accessor = false;
+                }
+                TokenTree::Literal(_) => {
+                    after_accessor = false;
+                }
+            }
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for ReferencedIdents {
+    fn visit_expr_path(&mut self, expr: &'ast ExprPath) {
+        if let Some(ident) = single_ident(expr) {
+            self.reference(ident);
+        } else {
+            visit::visit_expr_path(self, expr);
+        }
+    }
+
+    fn visit_expr_call(&mut self, expr: &'ast ExprCall) {
+        match *expr.func {
+            Expr::Path(ref func) if single_ident(func).is_some() => {}
+            ref func => self.visit_expr(func),
+        }
+        for arg in &expr.args {
+            self.visit_expr(arg);
+        }
+    }
+
+    fn visit_expr_closure(&mut self, expr: &'ast ExprClosure) {
+        self.scoped(move |this| {
+            for input in &expr.inputs {
+                this.bind(input);
+            }
+            this.visit_expr(&expr.body);
+        });
+    }
+
+    fn visit_block(&mut self, block: &'ast Block) {
+        self.scoped(move |this| {
+            for stmt in &block.stmts {
+                this.visit_stmt(stmt);
+            }
+        });
+    }
+
+    fn visit_local(&mut self, local: &'ast Local) {
+        // The initialiser can't see the names it's binding
+        if let Some(ref init) = local.init {
+            self.visit_expr(&init.expr);
+            if let Some((_, ref diverge)) = init.diverge {
+                self.visit_expr(diverge);
+            }
+        }
+        self.bind(&local.pat);
+    }
+
+    fn visit_expr_let(&mut self, expr: &'ast ExprLet) {
+        self.visit_expr(&expr.expr);
+        self.bind(&expr.pat);
+    }
+
+    fn visit_expr_if(&mut self, expr: &'ast ExprIf) {
+        // `if let` bindings are only visible in the `then` branch
+        self.scoped(move |this| {
+            this.visit_expr(&expr.cond);
+            this.visit_block(&expr.then_branch);
+        });
+        if let Some((_, ref else_branch)) = expr.else_branch {
+            self.visit_expr(else_branch);
+        }
+    }
+
+    fn visit_expr_while(&mut self, expr: &'ast ExprWhile) {
+        self.scoped(move |this| {
+            this.visit_expr(&expr.cond);
+            this.visit_block(&expr.body);
+        });
+    }
+
+    fn visit_arm(&mut self, arm: &'ast Arm) {
+        self.scoped(move |this| {
+            this.bind(&arm.pat);
+            if let Some((_, ref guard)) = arm.guard {
+                this.visit_expr(guard);
+            }
+            this.visit_expr(&arm.body);
+        });
+    }
+
+    fn visit_expr_for_loop(&mut self, expr: &'ast ExprForLoop) {
+        self.visit_expr(&expr.expr);
+        self.scoped(move |this| {
+            this.bind(&expr.pat);
+            this.visit_block(&expr.body);
+        });
+    }
+
+    fn visit_macro(&mut self, mac: &'ast Macro) {
+        let name = mac
+            .path
+            .segments
+            .last()
+            .map(move |seg| seg.ident.to_string());
+        // Position of the format string, if the macro takes one
+        let fmt_pos = match name.as_deref() {
+            Some(name) if FORMAT_MACROS.contains(&name) => Some(0),
+            Some(name) if WRITE_MACROS.contains(&name) => Some(1),
+            _ => None,
+        };
+
+        match (
+            mac.parse_body_with(Punctuated::<Expr, Token![,]>::parse_terminated),
+            fmt_pos,
+        ) {
+            (Ok(args), Some(fmt_pos)) => self.visit_format_args(&args, fmt_pos),
+            (Ok(args), None) => {
+                for arg in &args {
+                    self.visit_expr(arg);
+                }
+            }
+            (Err(_), _) => self.visit_tokens(mac.tokens.clone()),
+        }
+    }
+}
+
+/// Names bound by a pattern
+#[derive(Default)]
+struct PatBindings(Vec<Ident>);
+
+impl<'ast> Visit<'ast> for PatBindings {
+    fn visit_pat_ident(&mut self, pat: &'ast PatIdent) {
+        self.0.push(pat.ident.clone());
+        visit::visit_pat_ident(self, pat);
+    }
+}
+
+fn single_ident(expr: &ExprPath) -> Option<&Ident> {
+    if expr.qself.is_some() {
+        return None;
+    }
+
+    let Path {
+        ref leading_colon,
+        ref segments,
+    } = expr.path;
+    match segments.first() {
+        Some(seg) if leading_colon.is_none() && segments.len() == 1 && seg.arguments.is_none() => {
+            Some(&seg.ident)
+        }
+        _ => None,
+    }
+}
+
+/// Variables captured inline by a format string, e.g. `count` & `width` in `"{count:width$}"`
+fn format_captures(fmt: &LitStr) -> Vec<Ident> {
+    let fmt = fmt.value();
+    let mut out = Vec::new();
+    let mut chars = fmt.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        match ch {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+            }
+            '{' => {
+                let mut placeholder = String::new();
+                for ch in chars.by_ref() {
+                    if ch == '}' {
+                        break;
+                    }
+                    placeholder.push(ch);
+                }
+
+                let (arg, spec) = placeholder.split_once(':').unwrap_or((&placeholder, ""));
+                out.extend(parse_ident(arg.trim()));
+
+                // `name$` widths & precisions
+                let mut spec = spec;
+                while let Some(end) = spec.find('$') {
+                    let start = spec[..end]
+                        .rfind(move |c: char| !(c.is_alphanumeric() || c == '_'))
+                        .map_or(0, move |idx| idx + 1);
+                    out.extend(parse_ident(&spec[start..end]));
+                    spec = &spec[end + 1..];
+                }
+            }
+            _ => {}
+        }
+    }
+
+    out
+}
+
+/// Positional arguments & keywords aren't identifiers
+fn parse_ident(s: &str) -> Option<Ident> {
+    syn::parse_str(s).ok()
+}
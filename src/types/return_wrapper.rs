@@ -0,0 +1,112 @@
+use macroific::attr_parse::ParseOption;
+use proc_macro2::{Ident, TokenStream, TokenTree};
+use quote::{quote, ToTokens};
+use syn::parse::{Parse, ParseStream};
+use syn::{GenericArgument, Path, PathArguments, Type};
+
+/// A pointer type the constructed value gets wrapped in, e.g. `Arc` or `Pin<Box>`
+#[derive(ParseOption, Clone)]
+#[attr_opts(from_parse)]
+pub struct ReturnWrapper {
+    /// The pointer's path, with `Box`, `Rc` & `Arc` resolved to their `std` paths
+    pub pointer: TokenStream,
+
+    /// Whether the pointer should be wrapped in a `Pin`
+    pub pinned: bool,
+}
+
+impl ReturnWrapper {
+    /// The pointer used by `weak_self` constructors if none is specified
+    pub fn arc() -> Self {
+        Self {
+            pointer: quote!(::std::sync::Arc),
+            pinned: false,
+        }
+    }
+
+    /// Whether the pointer can be constructed via `new_cyclic`, i.e. is an `Arc` or `Rc`
+    pub fn is_cyclic(&self) -> bool {
+        let last = self
+            .pointer
+            .clone()
+            .into_iter()
+            .filter_map(move |token| match token {
+                TokenTree::Ident(ident) => Some(ident),
+                _ => None,
+            })
+            .last();
+
+        last.is_some_and(move |ident| ident == "Arc" || ident == "Rc")
+    }
+
+    /// The type wrapping `inner`
+    pub fn wrap_type(&self, inner: impl ToTokens) -> TokenStream {
+        let pointer = &self.pointer;
+        if self.pinned {
+            quote!(::core::pin::Pin<#pointer<#inner>>)
+        } else {
+            quote!(#pointer<#inner>)
+        }
+    }
+
+    /// The expression wrapping `value`
+    pub fn wrap_value(&self, value: impl ToTokens) -> TokenStream {
+        let pointer = &self.pointer;
+        if self.pinned {
+            quote!(#pointer::pin(#value))
+        } else {
+            quote!(#pointer::new(#value))
+        }
+    }
+
+    /// The expression wrapping `value` via `new_cyclic`, with the `Weak` handle bound to `weak`
+    pub fn wrap_cyclic_value(&self, weak: &Ident, value: impl ToTokens) -> TokenStream {
+        let pointer = &self.pointer;
+        quote!(#pointer::new_cyclic(move |#weak| #value))
+    }
+}
+
+impl Parse for ReturnWrapper {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let path = input.parse::<Path>()?;
+        let last = path.segments.last().expect("Paths can't be empty");
+
+        if last.ident != "Pin" {
+            return Ok(Self {
+                pointer: resolve_pointer(&path),
+                pinned: false,
+            });
+        }
+
+        if let PathArguments::AngleBracketed(ref args) = last.arguments {
+            if let (1, Some(GenericArgument::Type(Type::Path(ty)))) =
+                (args.args.len(), args.args.first())
+            {
+                if ty.qself.is_none() {
+                    return Ok(Self {
+                        pointer: resolve_pointer(&ty.path),
+                        pinned: true,
+                    });
+                }
+            }
+        }
+
+        Err(syn::Error::new_spanned(
+            path,
+            "Expected `Pin<Pointer>`, e.g. `Pin<Box>`",
+        ))
+    }
+}
+
+fn resolve_pointer(path: &Path) -> TokenStream {
+    let Some(ident) = path.get_ident() else {
+        return path.to_token_stream();
+    };
+
+    match ident.to_string().as_str() {
+        "Box" => quote!(::std::boxed::Box),
+        "Rc" => quote!(::std::rc::Rc),
+        "Arc" => quote!(::std::sync::Arc),
+        _ => path.to_token_stream(),
+    }
+}
@@ -0,0 +1,25 @@
+use macroific::attr_parse::{ParseOption, ValueSyntax};
+use syn::parse::ParseStream;
+use syn::LitStr;
+
+/// The `# Safety` section of an unsafe constructor's docs: `opt`, `opt("...")` or `opt = "..."`
+#[derive(Default, Clone)]
+pub struct SafetyDoc {
+    pub doc: Option<LitStr>,
+}
+
+impl ParseOption for SafetyDoc {
+    fn from_stream(input: ParseStream) -> syn::Result<Self> {
+        let Some(syntax) = ValueSyntax::from_stream(input) else {
+            return Ok(Self::default());
+        };
+
+        let doc = if let Some(content) = syntax.parse_token(input)? {
+            content.parse()?
+        } else {
+            input.parse()?
+        };
+
+        Ok(Self { doc: Some(doc) })
+    }
+}
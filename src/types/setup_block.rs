@@ -0,0 +1,48 @@
+use macroific::attr_parse::ParseOption;
+use proc_macro2::TokenStream;
+use quote::{quote, ToTokens, TokenStreamExt};
+use syn::parse::ParseStream;
+use syn::{braced, Block, Stmt, Token};
+
+/// Statements of the `setup` container option: `setup { stmts }` or `setup = { stmts }`
+#[derive(Clone)]
+pub struct SetupBlock {
+    pub stmts: Vec<Stmt>,
+}
+
+impl SetupBlock {
+    /// The statements for a constructor that shares them with others & needn't use everything
+    /// their `let`s bind
+    pub fn to_shared_tokens(&self) -> TokenStream {
+        let mut tokens = TokenStream::new();
+        for stmt in &self.stmts {
+            if let Stmt::Local(_) = *stmt {
+                tokens.extend(quote!(#[allow(unused_variables)]));
+            }
+            stmt.to_tokens(&mut tokens);
+        }
+
+        tokens
+    }
+}
+
+impl ParseOption for SetupBlock {
+    fn from_stream(input: ParseStream) -> syn::Result<Self> {
+        if input.peek(Token![=]) {
+            input.parse::<Token![=]>()?;
+        }
+
+        let content;
+        braced!(content in input);
+
+        Ok(Self {
+            stmts: content.call(Block::parse_within)?,
+        })
+    }
+}
+
+impl ToTokens for SetupBlock {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        tokens.append_all(&self.stmts);
+    }
+}